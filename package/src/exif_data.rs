@@ -3,8 +3,58 @@
 /// This module defines the data structures used to represent EXIF metadata
 /// extracted from image files, both RAW and regular formats.
 
+use crate::iptc_data::IptcInfo;
 use std::os::raw::c_char;
 
+/// Converts EXIF GPS degrees/minutes/seconds plus a reference character
+/// (`N`/`S`/`E`/`W`) to signed decimal degrees, negating when the
+/// reference is South or West.
+pub fn dms_to_decimal(degrees: f64, minutes: f64, seconds: f64, reference: char) -> f64 {
+    let decimal = degrees + minutes / 60.0 + seconds / 3600.0;
+    match reference.to_ascii_uppercase() {
+        'S' | 'W' => -decimal,
+        _ => decimal,
+    }
+}
+
+/// Parsed components of an EXIF `DateTimeOriginal`-style timestamp
+/// (`"YYYY:MM:DD HH:MM:SS"`), split out so callers can sort or compare
+/// chronologically without string hacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CaptureDate {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub min: u32,
+    pub sec: u32,
+}
+
+/// Parses an EXIF/LibRaw date string of the form `"YYYY:MM:DD HH:MM:SS"`
+/// into its components, validating the `4-2-2` date / `2:2:2` time shape.
+/// Returns `None` for empty or malformed values.
+fn parse_capture_date(date_taken: &str) -> Option<CaptureDate> {
+    let bytes = date_taken.as_bytes();
+    if bytes.len() != 19
+        || bytes[4] != b':'
+        || bytes[7] != b':'
+        || bytes[10] != b' '
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+
+    Some(CaptureDate {
+        year: date_taken[0..4].parse().ok()?,
+        month: date_taken[5..7].parse().ok()?,
+        day: date_taken[8..10].parse().ok()?,
+        hour: date_taken[11..13].parse().ok()?,
+        min: date_taken[14..16].parse().ok()?,
+        sec: date_taken[17..19].parse().ok()?,
+    })
+}
+
 /// C-compatible EXIF data structure for interfacing with LibRaw
 /// This structure must match the ExifData struct in libraw_wrapper.h
 #[repr(C)]
@@ -29,6 +79,36 @@ pub struct ExifData {
     pub focal_length_35mm: i32,
     pub description: *const c_char,
     pub artist: *const c_char,
+    /// Signed decimal degrees, already converted from the EXIF
+    /// DMS-plus-reference encoding. Only meaningful when `has_gps != 0`.
+    pub gps_latitude: f64,
+    pub gps_longitude: f64,
+    pub gps_altitude: f64,
+    /// Non-zero when the file carried a GPS IFD; `gps_latitude`/
+    /// `gps_longitude`/`gps_altitude` are unset (0.0) otherwise, which
+    /// would otherwise be indistinguishable from real coordinates at
+    /// (0, 0).
+    pub has_gps: i32,
+    /// Raw EXIF GPSLatitude triplet: [degrees, minutes, seconds].
+    /// Only meaningful when `has_gps != 0`.
+    pub gps_lat_dms: [f64; 3],
+    /// Raw EXIF GPSLongitude triplet: [degrees, minutes, seconds].
+    pub gps_lon_dms: [f64; 3],
+    /// EXIF GPSLatitudeRef: `'N'` or `'S'`.
+    pub gps_lat_ref: c_char,
+    /// EXIF GPSLongitudeRef: `'E'` or `'W'`.
+    pub gps_lon_ref: c_char,
+    /// EXIF GPSAltitudeRef byte: 0 = above sea level, 1 = below.
+    pub gps_altitude_ref: i32,
+    pub location_city: *const c_char,
+    pub location_country: *const c_char,
+    pub location_sublocation: *const c_char,
+    /// Dimensions of the camera-embedded preview pulled out by
+    /// `extract_raw_thumb`/`process_raw_preview`, independent of
+    /// `output_width`/`output_height` (which describe a full demosaic).
+    /// Zero when no embedded-preview extraction was performed.
+    pub embedded_preview_width: i32,
+    pub embedded_preview_height: i32,
 }
 
 /// Rust-native EXIF data structure for safe handling
@@ -77,6 +157,41 @@ pub struct ExifInfo {
     pub description: String,
     /// Artist or photographer name
     pub artist: String,
+    /// Latitude in signed decimal degrees (negative is South), converted
+    /// from the EXIF GPS IFD's degrees/minutes/seconds + reference
+    /// encoding. `None` when the file carries no GPS IFD.
+    pub gps_latitude: Option<f64>,
+    /// Longitude in signed decimal degrees (negative is West).
+    pub gps_longitude: Option<f64>,
+    /// Altitude in meters above sea level.
+    pub gps_altitude: Option<f64>,
+    /// Raw EXIF GPSLatitude triplet ([degrees, minutes, seconds]), kept
+    /// alongside the converted `gps_latitude` so callers can reconstruct
+    /// the exact EXIF-encoded value instead of only its decimal rounding.
+    pub gps_lat_dms: [f64; 3],
+    /// Raw EXIF GPSLongitude triplet ([degrees, minutes, seconds]).
+    pub gps_lon_dms: [f64; 3],
+    /// EXIF GPSLatitudeRef: `'N'` or `'S'`, or `'\0'` when there is no GPS IFD.
+    pub gps_lat_ref: char,
+    /// EXIF GPSLongitudeRef: `'E'` or `'W'`, or `'\0'` when there is no GPS IFD.
+    pub gps_lon_ref: char,
+    /// EXIF GPSAltitudeRef byte: 0 = above sea level, 1 = below.
+    pub gps_altitude_ref: i32,
+    /// City name from IPTC/XMP location metadata, when present.
+    pub location_city: String,
+    /// Country name from IPTC/XMP location metadata, when present.
+    pub location_country: String,
+    /// Sublocation (e.g. neighborhood or venue) from IPTC/XMP metadata.
+    pub location_sublocation: String,
+    /// Width of the camera-embedded preview in pixels, when extracted via
+    /// `extract_raw_thumb`/`process_raw_preview`. Zero otherwise.
+    pub embedded_preview_width: i32,
+    /// Height of the camera-embedded preview in pixels. Zero otherwise.
+    pub embedded_preview_height: i32,
+    /// IPTC/XMP descriptive metadata (caption, keywords, credit, location),
+    /// populated separately from the EXIF fields above. Empty when the
+    /// file carries no IPTC/XMP block or extraction was not attempted.
+    pub iptc: IptcInfo,
 }
 
 impl Default for ExifInfo {
@@ -103,6 +218,20 @@ impl Default for ExifInfo {
             focal_length_35mm: 0,
             description: String::new(),
             artist: String::new(),
+            gps_latitude: None,
+            gps_longitude: None,
+            gps_altitude: None,
+            gps_lat_dms: [0.0; 3],
+            gps_lon_dms: [0.0; 3],
+            gps_lat_ref: '\0',
+            gps_lon_ref: '\0',
+            gps_altitude_ref: 0,
+            location_city: String::new(),
+            location_country: String::new(),
+            location_sublocation: String::new(),
+            embedded_preview_width: 0,
+            embedded_preview_height: 0,
+            iptc: IptcInfo::default(),
         }
     }
 }
@@ -166,6 +295,57 @@ impl ExifInfo {
         }
     }
 
+    /// Checks if this EXIF info contains GPS coordinates
+    pub fn has_gps_info(&self) -> bool {
+        self.gps_latitude.is_some() && self.gps_longitude.is_some()
+    }
+
+    /// Recomputes latitude as signed decimal degrees directly from the raw
+    /// `gps_lat_dms` triplet and `gps_lat_ref`, independent of the
+    /// precomputed `gps_latitude` field. `None` when there is no GPS IFD.
+    pub fn decimal_latitude(&self) -> Option<f64> {
+        if !self.has_gps_info() {
+            return None;
+        }
+        let [deg, min, sec] = self.gps_lat_dms;
+        Some(dms_to_decimal(deg, min, sec, self.gps_lat_ref))
+    }
+
+    /// Recomputes longitude as signed decimal degrees directly from the raw
+    /// `gps_lon_dms` triplet and `gps_lon_ref`. `None` when there is no GPS IFD.
+    pub fn decimal_longitude(&self) -> Option<f64> {
+        if !self.has_gps_info() {
+            return None;
+        }
+        let [deg, min, sec] = self.gps_lon_dms;
+        Some(dms_to_decimal(deg, min, sec, self.gps_lon_ref))
+    }
+
+    /// Gets a formatted "latitude, longitude" string (e.g.
+    /// `"48.8584, 2.2945"`), or `"Unknown"` when no GPS data is present.
+    pub fn formatted_coordinates(&self) -> String {
+        match (self.gps_latitude, self.gps_longitude) {
+            (Some(lat), Some(lon)) => format!("{:.4}, {:.4}", lat, lon),
+            _ => "Unknown".to_string(),
+        }
+    }
+
+    /// Parses `date_taken` into its year/month/day/hour/min/sec components.
+    /// Returns `None` for empty or malformed values.
+    pub fn captured_date(&self) -> Option<CaptureDate> {
+        parse_capture_date(&self.date_taken)
+    }
+
+    /// Gets `date_taken` reformatted as ISO-8601 (`YYYY-MM-DDTHH:MM:SS`).
+    /// Returns `None` for empty or malformed values.
+    pub fn date_taken_iso8601(&self) -> Option<String> {
+        let date = parse_capture_date(&self.date_taken)?;
+        Some(format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            date.year, date.month, date.day, date.hour, date.min, date.sec
+        ))
+    }
+
     /// Gets image dimensions as a formatted string
     pub fn formatted_dimensions(&self) -> String {
         if self.raw_width > 0 && self.raw_height > 0 {
@@ -180,3 +360,97 @@ impl ExifInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dms_to_decimal_converts_paris_latitude() {
+        // 48 deg 51' 30.2" N
+        let decimal = dms_to_decimal(48.0, 51.0, 30.2, 'N');
+        assert!((decimal - 48.8584).abs() < 0.001);
+    }
+
+    #[test]
+    fn dms_to_decimal_negates_south_and_west() {
+        assert!(dms_to_decimal(10.0, 0.0, 0.0, 'S') < 0.0);
+        assert!(dms_to_decimal(10.0, 0.0, 0.0, 'W') < 0.0);
+        assert!(dms_to_decimal(10.0, 0.0, 0.0, 'N') > 0.0);
+    }
+
+    #[test]
+    fn formatted_coordinates_reports_unknown_without_gps() {
+        let exif = ExifInfo::default();
+        assert!(!exif.has_gps_info());
+        assert_eq!(exif.formatted_coordinates(), "Unknown");
+    }
+
+    #[test]
+    fn formatted_coordinates_with_gps_present() {
+        let exif = ExifInfo {
+            gps_latitude: Some(48.8584),
+            gps_longitude: Some(2.2945),
+            ..Default::default()
+        };
+        assert!(exif.has_gps_info());
+        assert_eq!(exif.formatted_coordinates(), "48.8584, 2.2945");
+    }
+
+    #[test]
+    fn decimal_latitude_and_longitude_match_dms_to_decimal() {
+        let exif = ExifInfo {
+            gps_latitude: Some(48.8584),
+            gps_longitude: Some(2.2945),
+            gps_lat_dms: [48.0, 51.0, 30.2],
+            gps_lon_dms: [2.0, 17.0, 40.2],
+            gps_lat_ref: 'N',
+            gps_lon_ref: 'E',
+            ..Default::default()
+        };
+        assert!((exif.decimal_latitude().unwrap() - 48.8584).abs() < 0.001);
+        assert!((exif.decimal_longitude().unwrap() - 2.2945).abs() < 0.001);
+    }
+
+    #[test]
+    fn decimal_latitude_and_longitude_none_without_gps() {
+        let exif = ExifInfo::default();
+        assert_eq!(exif.decimal_latitude(), None);
+        assert_eq!(exif.decimal_longitude(), None);
+    }
+
+    #[test]
+    fn date_taken_iso8601_normalizes_separators() {
+        let exif = ExifInfo {
+            date_taken: "2023:07:04 12:30:45".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            exif.date_taken_iso8601().as_deref(),
+            Some("2023-07-04T12:30:45")
+        );
+        assert_eq!(
+            exif.captured_date(),
+            Some(CaptureDate {
+                year: 2023,
+                month: 7,
+                day: 4,
+                hour: 12,
+                min: 30,
+                sec: 45,
+            })
+        );
+    }
+
+    #[test]
+    fn date_taken_iso8601_rejects_empty_and_malformed() {
+        assert_eq!(ExifInfo::default().date_taken_iso8601(), None);
+
+        let exif = ExifInfo {
+            date_taken: "not a date".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(exif.date_taken_iso8601(), None);
+        assert_eq!(exif.captured_date(), None);
+    }
+}
@@ -0,0 +1,339 @@
+/// EXIF APP1 segment writer
+///
+/// The RAW/JPEG wrappers never write the metadata captured into
+/// [`crate::exif_data::ExifInfo`] back into the output JPEG, so downstream
+/// tools that read the file back see none of it. This module builds a
+/// minimal TIFF IFD0/ExifIFD structure from an `ExifInfo` (camera
+/// make/model/software, artist/description, plus the ExifIFD's
+/// date/exposure/aperture/ISO/focal-length/lens fields and an Orientation tag)
+/// and splices it into a JPEG as an "Exif\0\0" APP1 segment right after
+/// the SOI marker.
+use crate::exif_data::ExifInfo;
+use std::fs;
+
+const TIFF_TYPE_ASCII: u16 = 2;
+const TIFF_TYPE_SHORT: u16 = 3;
+const TIFF_TYPE_LONG: u16 = 4;
+const TIFF_TYPE_RATIONAL: u16 = 5;
+
+const EXIF_IFD_POINTER: u16 = 0x8769;
+const ORIENTATION_TAG: u16 = 274;
+
+/// A single pending TIFF IFD entry: tag, type, element count, and the raw
+/// little-endian value bytes (inlined into the 4-byte value field if they
+/// fit, otherwise written to the IFD's trailing data area).
+struct IfdEntry {
+    tag: u16,
+    type_id: u16,
+    count: u32,
+    value: Vec<u8>,
+}
+
+fn ascii_value(s: &str) -> Vec<u8> {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
+
+fn rational_value(numerator: u32, denominator: u32) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(8);
+    bytes.extend_from_slice(&numerator.to_le_bytes());
+    bytes.extend_from_slice(&denominator.to_le_bytes());
+    bytes
+}
+
+/// Serializes an IFD (sorted by tag, as required by the TIFF spec) that
+/// starts at `data_start_abs` (an absolute offset from the start of the
+/// TIFF header); its trailing data area begins right after the IFD
+/// itself, at `data_start_abs + ifd_size`. Returns `(ifd_bytes, data_bytes)`; the
+/// "next IFD offset" field is always written as 0.
+fn serialize_ifd(mut entries: Vec<IfdEntry>, data_start_abs: u32) -> (Vec<u8>, Vec<u8>) {
+    entries.sort_by_key(|e| e.tag);
+
+    let ifd_size = 2 + entries.len() * 12 + 4;
+    let mut ifd_bytes = Vec::with_capacity(ifd_size);
+    let mut data_bytes = Vec::new();
+    let mut data_cursor = data_start_abs + ifd_size as u32;
+
+    ifd_bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for entry in &entries {
+        ifd_bytes.extend_from_slice(&entry.tag.to_le_bytes());
+        ifd_bytes.extend_from_slice(&entry.type_id.to_le_bytes());
+        ifd_bytes.extend_from_slice(&entry.count.to_le_bytes());
+
+        if entry.value.len() <= 4 {
+            let mut inline = entry.value.clone();
+            inline.resize(4, 0);
+            ifd_bytes.extend_from_slice(&inline);
+        } else {
+            ifd_bytes.extend_from_slice(&data_cursor.to_le_bytes());
+            let mut padded = entry.value.clone();
+            if padded.len() % 2 != 0 {
+                padded.push(0);
+            }
+            data_cursor += padded.len() as u32;
+            data_bytes.extend_from_slice(&padded);
+        }
+    }
+    ifd_bytes.extend_from_slice(&0u32.to_le_bytes()); // next IFD offset
+
+    (ifd_bytes, data_bytes)
+}
+
+/// Patches the inline 4-byte value field of `tag` within an already
+/// serialized IFD produced by `serialize_ifd`.
+fn patch_ifd_long(ifd_bytes: &mut [u8], tag: u16, value: u32) {
+    let count = u16::from_le_bytes([ifd_bytes[0], ifd_bytes[1]]) as usize;
+    for i in 0..count {
+        let entry_offset = 2 + i * 12;
+        let entry_tag = u16::from_le_bytes([ifd_bytes[entry_offset], ifd_bytes[entry_offset + 1]]);
+        if entry_tag == tag {
+            ifd_bytes[entry_offset + 8..entry_offset + 12].copy_from_slice(&value.to_le_bytes());
+            return;
+        }
+    }
+}
+
+/// Builds a complete "Exif\0\0" + TIFF IFD0/ExifIFD payload (without the
+/// APP1 marker header) from `exif`.
+///
+/// `orientation` is written as EXIF tag 274 (Orientation) on IFD0 when
+/// given. Output from this crate's demosaic/decode path is already
+/// oriented right-side-up, so callers normally pass `Some(1)` (normal)
+/// rather than omitting the tag, letting metadata-driven viewers skip
+/// re-reading pixel data to guess orientation.
+fn build_exif_app1_payload(exif: &ExifInfo, orientation: Option<u16>) -> Vec<u8> {
+    let mut ifd0_entries = vec![IfdEntry {
+        tag: EXIF_IFD_POINTER,
+        type_id: TIFF_TYPE_LONG,
+        count: 1,
+        value: 0u32.to_le_bytes().to_vec(), // patched once the offset is known
+    }];
+    if let Some(orientation) = orientation {
+        ifd0_entries.push(IfdEntry {
+            tag: ORIENTATION_TAG,
+            type_id: TIFF_TYPE_SHORT,
+            count: 1,
+            value: orientation.to_le_bytes().to_vec(),
+        });
+    }
+    if !exif.description.is_empty() {
+        ifd0_entries.push(IfdEntry {
+            tag: 270, // ImageDescription
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.description.len() as u32 + 1,
+            value: ascii_value(&exif.description),
+        });
+    }
+    if !exif.camera_make.is_empty() {
+        ifd0_entries.push(IfdEntry {
+            tag: 271, // Make
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.camera_make.len() as u32 + 1,
+            value: ascii_value(&exif.camera_make),
+        });
+    }
+    if !exif.camera_model.is_empty() {
+        ifd0_entries.push(IfdEntry {
+            tag: 272, // Model
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.camera_model.len() as u32 + 1,
+            value: ascii_value(&exif.camera_model),
+        });
+    }
+    if !exif.software.is_empty() {
+        ifd0_entries.push(IfdEntry {
+            tag: 305, // Software
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.software.len() as u32 + 1,
+            value: ascii_value(&exif.software),
+        });
+    }
+    if !exif.artist.is_empty() {
+        ifd0_entries.push(IfdEntry {
+            tag: 315, // Artist
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.artist.len() as u32 + 1,
+            value: ascii_value(&exif.artist),
+        });
+    }
+
+    // TIFF header occupies the first 8 bytes; IFD0 starts right after it.
+    let (mut ifd0_bytes, data0_bytes) = serialize_ifd(ifd0_entries, 8);
+
+    let mut exif_ifd_entries = Vec::new();
+    if exif.shutter > 0.0 {
+        // Express as a rational of 1/x for sub-second speeds (the common case).
+        let (num, den) = if exif.shutter >= 1.0 {
+            ((exif.shutter * 1000.0).round() as u32, 1000)
+        } else {
+            (1, (1.0 / exif.shutter).round() as u32)
+        };
+        exif_ifd_entries.push(IfdEntry {
+            tag: 33434, // ExposureTime
+            type_id: TIFF_TYPE_RATIONAL,
+            count: 1,
+            value: rational_value(num, den.max(1)),
+        });
+    }
+    if exif.aperture > 0.0 {
+        exif_ifd_entries.push(IfdEntry {
+            tag: 33437, // FNumber
+            type_id: TIFF_TYPE_RATIONAL,
+            count: 1,
+            value: rational_value((exif.aperture * 10.0).round() as u32, 10),
+        });
+    }
+    if exif.iso_speed > 0 {
+        exif_ifd_entries.push(IfdEntry {
+            tag: 34855, // ISOSpeedRatings
+            type_id: TIFF_TYPE_SHORT,
+            count: 1,
+            value: (exif.iso_speed as u16).to_le_bytes().to_vec(),
+        });
+    }
+    if exif.focal_length > 0.0 {
+        exif_ifd_entries.push(IfdEntry {
+            tag: 37386, // FocalLength
+            type_id: TIFF_TYPE_RATIONAL,
+            count: 1,
+            value: rational_value((exif.focal_length * 10.0).round() as u32, 10),
+        });
+    }
+    if exif.focal_length_35mm > 0 {
+        exif_ifd_entries.push(IfdEntry {
+            tag: 41989, // FocalLengthIn35mmFilm
+            type_id: TIFF_TYPE_SHORT,
+            count: 1,
+            value: (exif.focal_length_35mm as u16).to_le_bytes().to_vec(),
+        });
+    }
+    if !exif.date_taken.is_empty() {
+        exif_ifd_entries.push(IfdEntry {
+            tag: 36867, // DateTimeOriginal
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.date_taken.len() as u32 + 1,
+            value: ascii_value(&exif.date_taken),
+        });
+    }
+    if !exif.lens.is_empty() {
+        exif_ifd_entries.push(IfdEntry {
+            tag: 42036, // LensModel
+            type_id: TIFF_TYPE_ASCII,
+            count: exif.lens.len() as u32 + 1,
+            value: ascii_value(&exif.lens),
+        });
+    }
+
+    let exif_ifd_abs_offset = 8 + ifd0_bytes.len() as u32 + data0_bytes.len() as u32;
+    patch_ifd_long(&mut ifd0_bytes, EXIF_IFD_POINTER, exif_ifd_abs_offset);
+
+    let (exif_ifd_bytes, exif_data_bytes) = if exif_ifd_entries.is_empty() {
+        (Vec::new(), Vec::new())
+    } else {
+        serialize_ifd(exif_ifd_entries, exif_ifd_abs_offset)
+    };
+
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II"); // little-endian byte order
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes()); // offset to IFD0
+    tiff.extend_from_slice(&ifd0_bytes);
+    tiff.extend_from_slice(&data0_bytes);
+    tiff.extend_from_slice(&exif_ifd_bytes);
+    tiff.extend_from_slice(&exif_data_bytes);
+
+    let mut payload = Vec::with_capacity(tiff.len() + 6);
+    payload.extend_from_slice(b"Exif\0\0");
+    payload.extend_from_slice(&tiff);
+    payload
+}
+
+/// Splices an "Exif\0\0" APP1 segment built from `exif` into the JPEG at
+/// `jpeg_path`, right after the SOI marker. See [`build_exif_app1_payload`]
+/// for what `orientation` means.
+pub fn embed_exif_into_jpeg(
+    jpeg_path: &str,
+    exif: &ExifInfo,
+    orientation: Option<u16>,
+) -> Result<(), String> {
+    let payload = build_exif_app1_payload(exif, orientation);
+    let segment_len = payload.len() + 2; // APP1 length field includes itself
+    if segment_len > 0xFFFF {
+        return Err("EXIF payload too large for a single APP1 segment".to_string());
+    }
+
+    let original =
+        fs::read(jpeg_path).map_err(|e| format!("Failed to read {}: {}", jpeg_path, e))?;
+    if original.len() < 2 || original[0] != 0xFF || original[1] != 0xD8 {
+        return Err("Output file is not a valid JPEG (missing SOI marker)".to_string());
+    }
+
+    let mut out = Vec::with_capacity(original.len() + segment_len + 2);
+    out.extend_from_slice(&original[0..2]); // SOI
+    out.push(0xFF);
+    out.push(0xE1); // APP1
+    out.extend_from_slice(&(segment_len as u16).to_be_bytes());
+    out.extend_from_slice(&payload);
+    out.extend_from_slice(&original[2..]);
+
+    fs::write(jpeg_path, out).map_err(|e| format!("Failed to write {}: {}", jpeg_path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minimal_jpeg() -> Vec<u8> {
+        vec![0xFF, 0xD8, 0xFF, 0xD9] // SOI, EOI, no other segments
+    }
+
+    #[test]
+    fn embed_exif_into_jpeg_splices_app1_after_soi() {
+        let dir = std::env::temp_dir().join(format!(
+            "raw_preview_rs_exif_writer_test_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let jpeg_path = dir.join("test.jpg");
+        std::fs::write(&jpeg_path, minimal_jpeg()).unwrap();
+
+        let exif = ExifInfo {
+            camera_make: "Canon".to_string(),
+            camera_model: "EOS R5".to_string(),
+            iso_speed: 400,
+            ..Default::default()
+        };
+
+        embed_exif_into_jpeg(jpeg_path.to_str().unwrap(), &exif, Some(1)).unwrap();
+
+        let written = std::fs::read(&jpeg_path).unwrap();
+        assert_eq!(&written[0..2], &[0xFF, 0xD8]);
+        assert_eq!(&written[2..4], &[0xFF, 0xE1]);
+        let segment_len = u16::from_be_bytes([written[4], written[5]]) as usize;
+        assert_eq!(&written[6..10], b"Exif");
+
+        let marker_end = 4 + segment_len;
+        assert_eq!(&written[marker_end..marker_end + 2], &[0xFF, 0xD9]);
+
+        let _ = std::fs::remove_file(&jpeg_path);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn embed_exif_into_jpeg_rejects_non_jpeg() {
+        let dir = std::env::temp_dir().join(format!(
+            "raw_preview_rs_exif_writer_test_bad_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("not_a_jpeg.jpg");
+        std::fs::write(&path, b"not a jpeg").unwrap();
+
+        let result = embed_exif_into_jpeg(path.to_str().unwrap(), &ExifInfo::default(), Some(1));
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir(&dir);
+    }
+}
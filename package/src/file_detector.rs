@@ -1,7 +1,16 @@
 /// File type detection utilities for image processing
-/// 
+///
 /// This module provides functions to identify supported file formats
 /// including RAW files from various camera manufacturers and standard image formats.
+use crate::identify::{self, RawFormat};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The number of leading bytes read from a file to sniff its format.
+/// Matches `identify::SNIFF_LEN`; standard image magic numbers all live
+/// well within the first kilobyte.
+const SNIFF_LEN: usize = 1024;
 
 /// Checks if a file extension corresponds to a supported RAW format
 ///
@@ -113,6 +122,94 @@ pub fn get_file_type(filename: &str) -> &'static str {
     }
 }
 
+/// A standard (non-RAW) image container recognized by content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageKind {
+    Jpeg,
+    Png,
+    Tiff,
+    Bmp,
+    WebP,
+    /// ISO-BMFF image containers; see `identify::RawFormat::Heic`/`Avif`/`Jxl`.
+    Heic,
+    Avif,
+    Jxl,
+}
+
+/// The result of content-based file format detection, distinguishing RAW
+/// sensor formats from standard image containers so a caller doesn't have
+/// to match on `identify::RawFormat` just to ask "is this a RAW file?".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// A RAW (or RAW-adjacent) format, with the specific maker/container
+    /// identified by `identify::identify_raw_format`.
+    Raw(RawFormat),
+    /// A standard image container.
+    Image(ImageKind),
+    /// Not recognized from content.
+    Unknown,
+}
+
+/// Reads up to `SNIFF_LEN` bytes from the start of `path`.
+fn read_header(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0u8; SNIFF_LEN];
+    let mut total = 0;
+    loop {
+        match file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    buf.truncate(total);
+    Ok(buf)
+}
+
+/// Identifies a file format from its leading bytes, preferring RAW
+/// detection first and then falling back to standard image magic numbers.
+///
+/// A TIFF-magic header whose maker couldn't be identified
+/// (`RawFormat::UnknownTiff`) is reported here as a standalone
+/// `ImageKind::Tiff` rather than a RAW file: `identify::RawFormat`'s own
+/// judgment call ("still very likely a RAW file") is the right default
+/// for RAW-focused callers, but plain TIFF photos are common enough that
+/// this module's Raw/Image split would be misleading if it kept them
+/// lumped in with RAW files.
+pub fn detect_format_from_bytes(data: &[u8]) -> FileFormat {
+    match identify::identify_raw_format_from_bytes(data) {
+        RawFormat::UnknownTiff => return FileFormat::Image(ImageKind::Tiff),
+        RawFormat::Heic => return FileFormat::Image(ImageKind::Heic),
+        RawFormat::Avif => return FileFormat::Image(ImageKind::Avif),
+        RawFormat::Jxl => return FileFormat::Image(ImageKind::Jxl),
+        RawFormat::Unknown => {}
+        raw => return FileFormat::Raw(raw),
+    }
+
+    if data.len() >= 3 && data[0..3] == [0xFF, 0xD8, 0xFF] {
+        return FileFormat::Image(ImageKind::Jpeg);
+    }
+    if data.len() >= 8 && data[0..8] == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A] {
+        return FileFormat::Image(ImageKind::Png);
+    }
+    if data.len() >= 2 && &data[0..2] == b"BM" {
+        return FileFormat::Image(ImageKind::Bmp);
+    }
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return FileFormat::Image(ImageKind::WebP);
+    }
+
+    FileFormat::Unknown
+}
+
+/// Identifies the format of the file at `path` by sniffing its leading
+/// bytes. See `detect_format_from_bytes` for the detection rules.
+pub fn detect_format_from_path(path: &Path) -> io::Result<FileFormat> {
+    let header = read_header(path)?;
+    Ok(detect_format_from_bytes(&header))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,4 +249,89 @@ mod tests {
         assert_eq!(get_file_type("image.jpg"), "Image");
         assert_eq!(get_file_type("document.txt"), "Unknown");
     }
+
+    #[test]
+    fn detects_jpeg_by_content() {
+        let bytes = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10];
+        assert_eq!(
+            detect_format_from_bytes(&bytes),
+            FileFormat::Image(ImageKind::Jpeg)
+        );
+    }
+
+    #[test]
+    fn detects_png_by_content() {
+        let bytes = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+        assert_eq!(
+            detect_format_from_bytes(&bytes),
+            FileFormat::Image(ImageKind::Png)
+        );
+    }
+
+    #[test]
+    fn detects_bmp_by_content() {
+        let bytes = b"BM\0\0\0\0";
+        assert_eq!(
+            detect_format_from_bytes(bytes),
+            FileFormat::Image(ImageKind::Bmp)
+        );
+    }
+
+    #[test]
+    fn detects_webp_by_content() {
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(b"WEBP");
+        assert_eq!(
+            detect_format_from_bytes(&bytes),
+            FileFormat::Image(ImageKind::WebP)
+        );
+    }
+
+    #[test]
+    fn standalone_tiff_is_reported_as_image_not_raw() {
+        let mut bytes = b"II*\0".to_vec();
+        bytes.extend_from_slice(&[0u8; 16]);
+        assert_eq!(
+            detect_format_from_bytes(&bytes),
+            FileFormat::Image(ImageKind::Tiff)
+        );
+    }
+
+    #[test]
+    fn detects_cr2_as_raw_by_content() {
+        let mut bytes = b"II*\0".to_vec();
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(b"CR\x02\0");
+        bytes.extend_from_slice(&[0u8; 16]);
+        assert_eq!(
+            detect_format_from_bytes(&bytes),
+            FileFormat::Raw(RawFormat::Cr2)
+        );
+    }
+
+    #[test]
+    fn unknown_content_reports_unknown() {
+        assert_eq!(detect_format_from_bytes(b"plain text"), FileFormat::Unknown);
+    }
+
+    #[test]
+    fn content_detection_overrides_a_misleading_extension() {
+        use std::io::Write;
+
+        // A JPEG's bytes saved under a ".cr2" extension: content detection
+        // should win, since that's the whole point of sniffing at all.
+        let path = std::env::temp_dir().join("file_detector_test_mislabeled.cr2");
+        let mut f = File::create(&path).unwrap();
+        f.write_all(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10]).unwrap();
+        drop(f);
+
+        assert!(is_raw_file("file_detector_test_mislabeled.cr2"));
+        assert_eq!(
+            detect_format_from_path(&path).unwrap(),
+            FileFormat::Image(ImageKind::Jpeg)
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
 }
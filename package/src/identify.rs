@@ -0,0 +1,309 @@
+/// Content-based RAW format identification
+///
+/// Filename extensions are easy to get wrong (renamed files, files copied
+/// without an extension, formats the extension allow-list hasn't caught up
+/// with yet), so this module sniffs the first kilobyte of a file and
+/// recognizes it by its container/format signature instead.
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The number of leading bytes read from a file to sniff its format.
+const SNIFF_LEN: usize = 1024;
+
+/// A RAW (or RAW-adjacent container) format identified from file content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawFormat {
+    /// Canon CR2 (TIFF-based)
+    Cr2,
+    /// Canon CR3 (ISO-BMFF/HEIF-based)
+    Cr3,
+    /// Nikon NEF (TIFF-based)
+    Nef,
+    /// Sony ARW (TIFF-based)
+    Arw,
+    /// Adobe DNG (TIFF-based)
+    Dng,
+    /// Olympus ORF (TIFF-based)
+    Orf,
+    /// Panasonic RW2 (TIFF-based)
+    Rw2,
+    /// Fujifilm RAF
+    Raf,
+    /// Canon CRW (legacy, CIFF container)
+    Crw,
+    /// A TIFF-based container whose maker-specific IFD didn't match a
+    /// known manufacturer; still very likely a RAW file.
+    UnknownTiff,
+    /// HEIC (ISO-BMFF/HEIF-based). A preview/image container, not a RAW
+    /// sensor format, but one `bmff::extract_preview_and_exif` can read.
+    Heic,
+    /// AVIF (ISO-BMFF/HEIF-based). See `Heic`.
+    Avif,
+    /// Boxed JPEG XL (ISO-BMFF-based `.jxl` container, as opposed to a
+    /// bare JXL codestream, which has no `ftyp` box and isn't detected
+    /// here). See `Heic`.
+    Jxl,
+    /// Not recognized from content; callers may still fall back to the
+    /// file extension.
+    Unknown,
+}
+
+impl RawFormat {
+    /// Whether this format should be treated as a RAW file.
+    ///
+    /// `Heic`/`Avif`/`Jxl` are ISO-BMFF containers this crate can read,
+    /// but they hold a regular photo or preview rather than sensor RAW
+    /// data, so they're excluded here alongside `Unknown`.
+    pub fn is_raw(self) -> bool {
+        !matches!(
+            self,
+            RawFormat::Unknown | RawFormat::Heic | RawFormat::Avif | RawFormat::Jxl
+        )
+    }
+}
+
+/// Reads up to `SNIFF_LEN` bytes from the start of `path`.
+fn read_header(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0u8; SNIFF_LEN];
+    let mut total = 0;
+    loop {
+        match file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    buf.truncate(total);
+    Ok(buf)
+}
+
+fn is_tiff_magic(header: &[u8]) -> Option<bool> {
+    if header.len() < 4 {
+        return None;
+    }
+    if &header[0..4] == b"II*\0" {
+        Some(true) // little-endian TIFF
+    } else if &header[0..4] == [0x4D, 0x4D, 0x00, 0x2A] {
+        Some(false) // big-endian TIFF ("MM\0*")
+    } else {
+        None
+    }
+}
+
+/// Probes a TIFF-magic header for a maker-specific signature in IFD0's
+/// ASCII tags, which is the cheapest way to tell CR2/NEF/ARW/DNG/ORF/RW2
+/// apart without a full TIFF parse. This is a heuristic substring scan
+/// over the sniffed bytes rather than a real IFD walk, which is good
+/// enough since these strings reliably appear within the first kilobyte.
+fn probe_tiff_maker(header: &[u8]) -> RawFormat {
+    let contains = |needle: &[u8]| header.windows(needle.len()).any(|w| w == needle);
+
+    // CR2 has a distinctive "CR\x02\0" marker right after the TIFF header.
+    if header.len() >= 10 && &header[8..10] == b"CR" {
+        return RawFormat::Cr2;
+    }
+    if contains(b"NIKON") {
+        return RawFormat::Nef;
+    }
+    if contains(b"SONY") {
+        return RawFormat::Arw;
+    }
+    if contains(b"OLYMPUS") {
+        return RawFormat::Orf;
+    }
+    if contains(b"Panasonic") {
+        return RawFormat::Rw2;
+    }
+    if contains(b"DNG") || contains(b"Adobe") {
+        return RawFormat::Dng;
+    }
+    RawFormat::UnknownTiff
+}
+
+/// Identifies the RAW (or RAW-adjacent) format from an already-read
+/// header buffer (at least the leading `SNIFF_LEN` bytes, though fewer
+/// will do for most signatures). This is the content-sniffing core that
+/// `identify_raw_format` and `file_detector::detect_format_from_bytes`
+/// both build on, split out so in-memory buffers don't need a round trip
+/// through a temp file.
+pub fn identify_raw_format_from_bytes(header: &[u8]) -> RawFormat {
+    if let Some(_little_endian) = is_tiff_magic(header) {
+        return probe_tiff_maker(header);
+    }
+
+    // Fujifilm RAF files start with an ASCII magic string.
+    if header.starts_with(b"FUJIFILMCCD-RAW") {
+        return RawFormat::Raf;
+    }
+
+    // Legacy Canon CRW uses the CIFF container, identified by a "HEAPCCDR"
+    // heap marker shortly after a RIFF-style header.
+    if header.len() >= 14 && &header[0..4] == b"II\x1a\0" && &header[6..14] == b"HEAPCCDR" {
+        return RawFormat::Crw;
+    }
+
+    // ISO-BMFF (HEIF/CR3/AVIF) files declare their brand in an `ftyp` box:
+    // a 4-byte big-endian box size, the ASCII tag "ftyp", then a 4-byte
+    // major brand. CR3 uses the "crx " brand; HEIC and AVIF each have a
+    // handful of brands in real-world use.
+    if header.len() >= 12 && &header[4..8] == b"ftyp" {
+        let brand = &header[8..12];
+        if brand == b"crx " {
+            return RawFormat::Cr3;
+        }
+        if matches!(brand, b"heic" | b"heix" | b"hevc" | b"hevx" | b"mif1") {
+            return RawFormat::Heic;
+        }
+        if matches!(brand, b"avif" | b"avis") {
+            return RawFormat::Avif;
+        }
+        if brand == b"jxl " {
+            return RawFormat::Jxl;
+        }
+    }
+
+    RawFormat::Unknown
+}
+
+/// Identifies the RAW (or RAW-adjacent) format of `path` by sniffing its
+/// content. Falls back to `RawFormat::Unknown` when the magic is
+/// ambiguous or absent; callers that still want an answer should fall
+/// back to extension-based detection (e.g. `file_detector::is_raw_file`).
+pub fn identify_raw_format(path: &Path) -> io::Result<RawFormat> {
+    let header = read_header(path)?;
+    Ok(identify_raw_format_from_bytes(&header))
+}
+
+/// Identifies the format by content, falling back to the filename
+/// extension when the sniff is ambiguous.
+pub fn identify_with_extension_fallback(path: &Path) -> io::Result<RawFormat> {
+    let detected = identify_raw_format(path)?;
+    if detected != RawFormat::Unknown {
+        return Ok(detected);
+    }
+
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    Ok(match ext.as_str() {
+        "cr2" => RawFormat::Cr2,
+        "cr3" => RawFormat::Cr3,
+        "nef" => RawFormat::Nef,
+        "arw" => RawFormat::Arw,
+        "dng" => RawFormat::Dng,
+        "orf" => RawFormat::Orf,
+        "rw2" => RawFormat::Rw2,
+        "raf" => RawFormat::Raf,
+        "crw" => RawFormat::Crw,
+        "heic" | "heif" => RawFormat::Heic,
+        "avif" => RawFormat::Avif,
+        "jxl" => RawFormat::Jxl,
+        _ => RawFormat::Unknown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp(bytes: &[u8], name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let mut f = File::create(&path).unwrap();
+        f.write_all(bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn detects_raf_magic() {
+        let path = write_temp(b"FUJIFILMCCD-RAW 0201", "identify_test_raf.raf");
+        assert_eq!(identify_raw_format(&path).unwrap(), RawFormat::Raf);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn detects_cr3_ftyp_box() {
+        let mut bytes = vec![0u8, 0, 0, 24];
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(b"crx ");
+        bytes.extend_from_slice(&[0u8; 16]);
+        let path = write_temp(&bytes, "identify_test_cr3.cr3");
+        assert_eq!(identify_raw_format(&path).unwrap(), RawFormat::Cr3);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn detects_cr2_tiff_marker() {
+        let mut bytes = b"II*\0".to_vec();
+        bytes.extend_from_slice(&[0u8; 4]);
+        bytes.extend_from_slice(b"CR\x02\0");
+        bytes.extend_from_slice(&[0u8; 16]);
+        let path = write_temp(&bytes, "identify_test_cr2.cr2");
+        assert_eq!(identify_raw_format(&path).unwrap(), RawFormat::Cr2);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn detects_heic_ftyp_box() {
+        let mut bytes = vec![0u8, 0, 0, 24];
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(b"heic");
+        bytes.extend_from_slice(&[0u8; 16]);
+        let path = write_temp(&bytes, "identify_test_heic.heic");
+        let format = identify_raw_format(&path).unwrap();
+        assert_eq!(format, RawFormat::Heic);
+        assert!(!format.is_raw());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn detects_avif_ftyp_box() {
+        let mut bytes = vec![0u8, 0, 0, 24];
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(b"avif");
+        bytes.extend_from_slice(&[0u8; 16]);
+        let path = write_temp(&bytes, "identify_test_avif.avif");
+        assert_eq!(identify_raw_format(&path).unwrap(), RawFormat::Avif);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn detects_jxl_ftyp_box() {
+        let mut bytes = vec![0u8, 0, 0, 24];
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(b"jxl ");
+        bytes.extend_from_slice(&[0u8; 16]);
+        let path = write_temp(&bytes, "identify_test_jxl.jxl");
+        let format = identify_raw_format(&path).unwrap();
+        assert_eq!(format, RawFormat::Jxl);
+        assert!(!format.is_raw());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn falls_back_to_extension_when_ambiguous() {
+        let path = write_temp(b"not a raw file at all", "identify_test_fallback.dng");
+        assert_eq!(
+            identify_with_extension_fallback(&path).unwrap(),
+            RawFormat::Dng
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn unknown_for_unrecognized_content_and_extension() {
+        let path = write_temp(b"plain text", "identify_test_unknown.txt");
+        assert_eq!(identify_raw_format(&path).unwrap(), RawFormat::Unknown);
+        assert_eq!(
+            identify_with_extension_fallback(&path).unwrap(),
+            RawFormat::Unknown
+        );
+        let _ = std::fs::remove_file(path);
+    }
+}
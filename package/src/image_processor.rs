@@ -5,9 +5,12 @@
 /// for other image formats.
 use crate::exif_data::{ExifData, ExifInfo};
 use crate::process_image_to_jpeg;
+use std::collections::hash_map::DefaultHasher;
 use std::ffi::CString;
+use std::hash::{Hash, Hasher};
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Processes a JPEG image file with EXIF extraction
 ///
@@ -141,6 +144,55 @@ pub fn process_image_file(input_path: &str, output_path: &str) -> Result<ExifInf
     })
 }
 
+/// Monotonic counter appended to `extract_image_metadata`'s temp path, so
+/// two calls racing on the same thread (or two threads that happen to
+/// land on the same tick) don't collide.
+static METADATA_TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Builds a per-call-unique temp path for `extract_image_metadata`. PID
+/// alone isn't enough -- callers like `main.rs`'s batch/tethered-capture
+/// thread pool can have multiple threads in the same process call this
+/// concurrently on non-RAW files, and a shared path means one thread's
+/// `remove_file` can delete the file out from under another's in-flight
+/// read.
+fn unique_metadata_temp_path() -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    std::thread::current().id().hash(&mut hasher);
+    let thread_id = hasher.finish();
+    let counter = METADATA_TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "raw_preview_rs_metadata_{}_{:016x}_{}.jpg",
+        std::process::id(),
+        thread_id,
+        counter
+    ))
+}
+
+/// Extracts EXIF metadata from a standard image file without writing a
+/// preview.
+///
+/// The underlying `libjpeg_wrapper` FFI call always produces an output
+/// file, so this routes through a throwaway temp path and removes it
+/// afterward -- still far cheaper for callers than asking them to manage
+/// (and clean up) a real preview file just to read tags.
+///
+/// # Arguments
+/// * `input_path` - Path to the input image file
+///
+/// # Returns
+/// * `Ok(ExifInfo)` with extracted metadata on success
+/// * `Err(String)` with error message on failure
+pub fn extract_image_metadata(input_path: &str) -> Result<ExifInfo, String> {
+    let temp_output = unique_metadata_temp_path();
+    let temp_output_str = temp_output
+        .to_str()
+        .ok_or("Invalid temporary output path")?;
+
+    let result = process_any_standard_image(input_path, temp_output_str);
+    let _ = std::fs::remove_file(&temp_output);
+    result
+}
+
 /// Processes any supported image file (JPEG, PNG, TIFF, etc.) with appropriate handling
 ///
 /// This is a convenience function that automatically detects the image type
@@ -179,6 +231,13 @@ mod tests {
         assert!(result.unwrap_err().contains("does not exist"));
     }
 
+    #[test]
+    fn metadata_temp_path_is_unique_per_call() {
+        let a = unique_metadata_temp_path();
+        let b = unique_metadata_temp_path();
+        assert_ne!(a, b);
+    }
+
     #[test]
     fn test_exif_info_for_image_types() {
         let jpeg_info = ExifInfo::for_jpeg_file();
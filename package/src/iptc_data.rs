@@ -0,0 +1,192 @@
+/// IPTC/XMP descriptive metadata structures and utilities
+///
+/// This module defines the data structures used to represent IPTC/XMP
+/// descriptive metadata (captions, keywords, credit/copyright, location)
+/// extracted from image files, complementing the camera/exposure EXIF
+/// data in [`crate::exif_data`].
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+/// Maximum number of keyword entries `extract_iptc` will populate.
+/// IPTC keyword records are repeated (not overwritten), so a fixed
+/// upper bound keeps the C struct `#[repr(C)]`-friendly.
+pub const MAX_IPTC_KEYWORDS: usize = 64;
+
+/// C-compatible IPTC data structure for interfacing with the metadata
+/// wrapper. This structure must match the IptcData struct in
+/// libraw_wrapper.h
+#[repr(C)]
+pub struct IptcData {
+    pub keyword_count: i32,
+    pub keywords: [*const c_char; MAX_IPTC_KEYWORDS],
+    pub caption: *const c_char,
+    pub headline: *const c_char,
+    pub copyright: *const c_char,
+    pub creator: *const c_char,
+    pub city: *const c_char,
+    pub country: *const c_char,
+}
+
+/// Rust-native IPTC/XMP data structure for safe handling
+///
+/// Represents descriptive metadata extracted from image files in a
+/// safe, owned format that can be easily used throughout the
+/// application.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IptcInfo {
+    /// Keyword/tag records. Accumulated rather than overwritten, since
+    /// IPTC stores keywords as repeated values.
+    pub keywords: Vec<String>,
+    /// Caption or description of the image content.
+    pub caption: String,
+    /// Headline summarizing the image.
+    pub headline: String,
+    /// Copyright notice.
+    pub copyright: String,
+    /// Creator/photographer byline.
+    pub creator: String,
+    /// City name from IPTC location metadata.
+    pub city: String,
+    /// Country name from IPTC location metadata.
+    pub country: String,
+}
+
+unsafe extern "C" {
+    #[link_name = "extract_iptc"]
+    fn extract_iptc_c(input_path: *const c_char, iptc_data: *mut IptcData) -> i32;
+    fn get_last_error() -> *const c_char;
+}
+
+const EXTRACT_SUCCESS: i32 = 0;
+
+/// Helper function to safely convert C string pointers to Rust strings
+fn safe_string_from_ptr(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() }
+    }
+}
+
+/// Extracts IPTC/XMP descriptive metadata (keywords, caption, headline,
+/// copyright, creator, location) from an image file.
+///
+/// Keyword records are repeated in IPTC, so all populated entries up to
+/// `MAX_IPTC_KEYWORDS` are accumulated into the returned vector rather
+/// than overwriting one another.
+///
+/// # Arguments
+/// * `input_path` - Path to the input image file (RAW or standard format)
+///
+/// # Returns
+/// * `Ok(IptcInfo)` with extracted descriptive metadata on success
+/// * `Err(String)` with detailed error message on failure
+pub fn extract_iptc(input_path: &str) -> Result<IptcInfo, String> {
+    let input_cstring = CString::new(input_path)
+        .map_err(|e| format!("Invalid input path '{}': {}", input_path, e))?;
+
+    let mut data = IptcData {
+        keyword_count: 0,
+        keywords: [std::ptr::null(); MAX_IPTC_KEYWORDS],
+        caption: std::ptr::null(),
+        headline: std::ptr::null(),
+        copyright: std::ptr::null(),
+        creator: std::ptr::null(),
+        city: std::ptr::null(),
+        country: std::ptr::null(),
+    };
+
+    let result = unsafe { extract_iptc_c(input_cstring.as_ptr(), &mut data) };
+
+    if result == EXTRACT_SUCCESS {
+        let keyword_count = (data.keyword_count as usize).min(MAX_IPTC_KEYWORDS);
+        let keywords = data.keywords[..keyword_count]
+            .iter()
+            .map(|&ptr| safe_string_from_ptr(ptr))
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        Ok(IptcInfo {
+            keywords,
+            caption: safe_string_from_ptr(data.caption),
+            headline: safe_string_from_ptr(data.headline),
+            copyright: safe_string_from_ptr(data.copyright),
+            creator: safe_string_from_ptr(data.creator),
+            city: safe_string_from_ptr(data.city),
+            country: safe_string_from_ptr(data.country),
+        })
+    } else {
+        let error_msg = unsafe {
+            let error_ptr = get_last_error();
+            if !error_ptr.is_null() {
+                CStr::from_ptr(error_ptr).to_string_lossy().into_owned()
+            } else {
+                "Unknown error".to_string()
+            }
+        };
+        Err(format!("IPTC extraction error {}: {}", result, error_msg))
+    }
+}
+
+impl IptcInfo {
+    /// Checks if this IPTC info contains any descriptive metadata at all.
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+            && self.caption.is_empty()
+            && self.headline.is_empty()
+            && self.copyright.is_empty()
+            && self.creator.is_empty()
+            && self.city.is_empty()
+            && self.country.is_empty()
+    }
+
+    /// Gets a comma-separated keyword string, or `"None"` when empty.
+    pub fn formatted_keywords(&self) -> String {
+        if self.keywords.is_empty() {
+            "None".to_string()
+        } else {
+            self.keywords.join(", ")
+        }
+    }
+
+    /// Alias for `creator` using the IPTC-IIM dataset's own name for the
+    /// field (`2:80`, By-line), for callers working against that spec
+    /// rather than this crate's field names.
+    pub fn byline(&self) -> &str {
+        &self.creator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_empty_true_for_default() {
+        assert!(IptcInfo::default().is_empty());
+    }
+
+    #[test]
+    fn is_empty_false_with_keywords() {
+        let iptc = IptcInfo {
+            keywords: vec!["sunset".to_string(), "beach".to_string()],
+            ..Default::default()
+        };
+        assert!(!iptc.is_empty());
+        assert_eq!(iptc.formatted_keywords(), "sunset, beach");
+    }
+
+    #[test]
+    fn formatted_keywords_reports_none_when_empty() {
+        assert_eq!(IptcInfo::default().formatted_keywords(), "None");
+    }
+
+    #[test]
+    fn byline_aliases_creator() {
+        let iptc = IptcInfo {
+            creator: "Jane Doe".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(iptc.byline(), "Jane Doe");
+    }
+}
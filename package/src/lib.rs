@@ -24,15 +24,35 @@ pub mod exif_data;
 ///     Err(e) => eprintln!("Processing failed: {}", e),
 /// }
 /// ```
+pub mod bmff;
+pub mod exif_writer;
 pub mod file_detector;
+pub mod identify;
 pub mod image_processor;
+pub mod iptc_data;
+pub mod privacy;
 pub mod raw_processor;
+pub mod thumbnail;
 
 // Re-export the main public API
-pub use exif_data::ExifInfo;
-pub use file_detector::{get_file_type, is_image_file, is_raw_file, is_supported_file};
+pub use bmff::{extract_preview_and_exif, BmffKind};
+pub use exif_data::{CaptureDate, ExifInfo};
+pub use exif_writer::embed_exif_into_jpeg;
+pub use file_detector::{
+    detect_format_from_bytes, detect_format_from_path, get_file_type, is_image_file, is_raw_file,
+    is_supported_file, FileFormat, ImageKind,
+};
+pub use identify::{identify_raw_format, identify_with_extension_fallback, RawFormat};
 pub use image_processor::process_image_file;
-pub use raw_processor::convert_raw_to_jpeg;
+pub use iptc_data::{extract_iptc, IptcInfo};
+pub use privacy::{redact_tags, strip_metadata};
+pub use raw_processor::{
+    convert_raw_bytes_to_vec_with_options, convert_raw_to_format, convert_raw_to_jpeg,
+    convert_raw_to_jpeg_with, extract_embedded_preview_bytes, extract_embedded_preview_from_path,
+    extract_raw_metadata, has_embedded_preview, process_raw, process_raw_preview, ColorSpace,
+    OutputFormat, OutputOptions, PreviewError, ProcessingMode, RawOptions, WhiteBalance,
+};
+pub use thumbnail::{get_or_create_thumbnail, ThumbnailFilter};
 
 use std::path::Path;
 
@@ -105,18 +125,77 @@ pub fn process_any_image(input_path: &str, output_path: &str) -> Result<ExifInfo
         .and_then(|name| name.to_str())
         .ok_or_else(|| format!("Invalid input path: {}", input_path))?;
 
-    // Route to appropriate processor based on file type
-    if is_raw_file(filename) {
-        convert_raw_to_jpeg(input_path, output_path)
-    } else if is_image_file(filename) {
-        // Use image_processor for all standard image files (JPEG, PNG, TIFF, etc.)
-        process_image_file(input_path, output_path)
-    } else {
-        Err(format!(
-            "Unsupported file format: '{}'. Supported formats include RAW files (CR2, CR3, NEF, ARW, etc.) and image files (JPG, PNG, TIFF, etc.)",
-            filename
-        ))
+    // Route to appropriate processor based on file type. Content detection
+    // takes priority when it confidently recognizes the file -- a renamed
+    // or extension-less file shouldn't be misrouted -- falling back to the
+    // extension-based check only when the content sniff is inconclusive.
+    let mut exif = match detect_format_from_path(Path::new(input_path)) {
+        Ok(FileFormat::Raw(_)) => convert_raw_to_jpeg(input_path, output_path),
+        Ok(FileFormat::Image(_)) => process_image_file(input_path, output_path),
+        Ok(FileFormat::Unknown) | Err(_) => {
+            if is_raw_file(filename) {
+                convert_raw_to_jpeg(input_path, output_path)
+            } else if is_image_file(filename) {
+                process_image_file(input_path, output_path)
+            } else {
+                Err(format!(
+                    "Unsupported file format: '{}'. Supported formats include RAW files (CR2, CR3, NEF, ARW, etc.) and image files (JPG, PNG, TIFF, etc.)",
+                    filename
+                ))
+            }
+        }
+    }?;
+
+    // IPTC/XMP descriptive metadata is optional -- many files carry none --
+    // so a failed or empty extraction just leaves `exif.iptc` at its default.
+    if let Ok(iptc) = extract_iptc(input_path) {
+        exif.iptc = iptc;
     }
+
+    Ok(exif)
+}
+
+/// Extracts EXIF metadata from any supported image file (RAW or standard
+/// format) without writing a preview.
+///
+/// Indexers and catalog tools that only need tags -- not a rendered
+/// preview -- can use this instead of `process_any_image` to skip the
+/// cost of decoding and encoding an output image entirely.
+///
+/// # Arguments
+/// * `input_path` - Path to the input image file (RAW or standard format)
+///
+/// # Returns
+/// * `Ok(ExifInfo)` with extracted metadata on success
+/// * `Err(String)` with detailed error message on failure
+pub fn extract_metadata(input_path: &str) -> Result<ExifInfo, String> {
+    let filename = Path::new(input_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Invalid input path: {}", input_path))?;
+
+    let mut exif = match detect_format_from_path(Path::new(input_path)) {
+        Ok(FileFormat::Raw(_)) => raw_processor::extract_raw_metadata(input_path),
+        Ok(FileFormat::Image(_)) => image_processor::extract_image_metadata(input_path),
+        Ok(FileFormat::Unknown) | Err(_) => {
+            if is_raw_file(filename) {
+                raw_processor::extract_raw_metadata(input_path)
+            } else if is_image_file(filename) {
+                image_processor::extract_image_metadata(input_path)
+            } else {
+                Err(format!(
+                    "Unsupported file format: '{}'. Supported formats include RAW files (CR2, CR3, NEF, ARW, etc.) and image files (JPG, PNG, TIFF, etc.)",
+                    filename
+                ))
+            }
+        }
+    }?;
+
+    if let Ok(iptc) = extract_iptc(input_path) {
+        exif.iptc = iptc;
+    }
+
+    Ok(exif)
 }
 
 /// Checks if a file can be processed by this library
@@ -167,9 +246,9 @@ pub fn get_file_info(input_path: &str) -> String {
         .unwrap_or("unknown");
 
     match get_file_type(filename) {
-        "RAW" => format!("RAW file (will be processed with LibRaw)"),
-        "Image" => format!("Standard image file (will be processed with libjpeg_wrapper)"),
-        _ => format!("Unsupported file format"),
+        "RAW" => "RAW file (will be processed with LibRaw)".to_string(),
+        "Image" => "Standard image file (will be processed with libjpeg_wrapper)".to_string(),
+        _ => "Unsupported file format".to_string(),
     }
 }
 
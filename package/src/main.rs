@@ -3,7 +3,17 @@ use std::fs;
 use std::os::raw::c_char;
 use std::path::Path;
 use std::ptr;
-use std::time::Instant;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+use raw_preview_rs::{exif_writer, ExifInfo};
+
+mod capture_source;
+use capture_source::InputSource;
 
 // Foreign function interface to our C++ wrapper
 unsafe extern "C" {
@@ -41,6 +51,44 @@ struct ExifData {
 
 const RW_SUCCESS: i32 = 0;
 
+/// Number of worker threads to use for batch processing.
+///
+/// Defaults to `num_cpus::get()` on first use; override with
+/// `set_number_of_threads` before the batch loop starts (LibRaw's working
+/// set per image is large, so callers on memory-constrained machines may
+/// want to cap this below the core count).
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the number of threads used by the batch processing pool.
+///
+/// Must be called before the first call to `run_batch` / the pool
+/// being built, since rayon thread pools are immutable once created.
+fn set_number_of_threads(threads: usize) {
+    NUM_THREADS.store(threads, Ordering::SeqCst);
+}
+
+/// Gets the number of threads that will be used for batch processing,
+/// resolving to `num_cpus::get()` if no explicit value was set.
+fn get_number_of_threads() -> usize {
+    let configured = NUM_THREADS.load(Ordering::SeqCst);
+    if configured == 0 {
+        num_cpus::get()
+    } else {
+        configured
+    }
+}
+
+static THREAD_POOL: OnceLock<ThreadPool> = OnceLock::new();
+
+fn thread_pool() -> &'static ThreadPool {
+    THREAD_POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(get_number_of_threads())
+            .build()
+            .expect("Failed to build rayon thread pool")
+    })
+}
+
 fn process_raw_to_jpeg(input_path: &str, output_path: &str, quality: u8) -> Result<(), String> {
     let input_cstring =
         CString::new(input_path).map_err(|e| format!("Invalid input path: {}", e))?;
@@ -79,6 +127,15 @@ fn process_raw_to_jpeg(input_path: &str, output_path: &str, quality: u8) -> Resu
     };
 
     if result == RW_SUCCESS {
+        // Embed the EXIF data LibRaw extracted into the JPEG's APP1 segment.
+        // A failure here shouldn't fail the conversion itself -- the JPEG is
+        // already on disk and usable without metadata.
+        let exif_info = exif_info_from(&exif_data);
+        // Output from process_raw_to_ppm is already oriented right-side-up,
+        // so Orientation is always written as 1 (normal).
+        if let Err(e) = exif_writer::embed_exif_into_jpeg(output_path, &exif_info, Some(1)) {
+            eprintln!("  ⚠ Failed to embed EXIF metadata into {}: {}", output_path, e);
+        }
         Ok(())
     } else {
         let error_msg = unsafe {
@@ -93,75 +150,169 @@ fn process_raw_to_jpeg(input_path: &str, output_path: &str, quality: u8) -> Resu
     }
 }
 
-fn main() {
-    println!("RAW to JPEG Converter using LibRaw and libjpeg-turbo");
-    println!("===================================");
+// --- EXIF embedding ------------------------------------------------------
+//
+// LibRaw populates `ExifData` with the camera/MakerNote fields, but
+// `process_raw_to_ppm` never writes them into the output JPEG. `exif_info_from`
+// adapts this binary's local, raw-pointer `ExifData` into the library's
+// `ExifInfo`, which `exif_writer::embed_exif_into_jpeg` then splices into the
+// JPEG as an "Exif\0\0" APP1 segment, so photo managers that key off embedded
+// EXIF pick up the camera settings.
 
-    let test_raws_dir = "../test_raws";
-    let output_dir = "../output";
+fn cstr_field(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(ptr).to_string_lossy().into_owned() }
+    }
+}
 
-    if let Err(e) = fs::create_dir_all(output_dir) {
-        eprintln!("Failed to create output directory: {}", e);
-        return;
+/// Builds an `ExifInfo` from the fields LibRaw populated. Fields this
+/// binary's `ExifData` doesn't carry (GPS, location, IPTC, embedded-preview
+/// dimensions) are left at their `Default` values.
+fn exif_info_from(exif: &ExifData) -> ExifInfo {
+    ExifInfo {
+        camera_make: cstr_field(exif.camera_make),
+        camera_model: cstr_field(exif.camera_model),
+        software: cstr_field(exif.software),
+        iso_speed: exif.iso_speed,
+        shutter: exif.shutter,
+        aperture: exif.aperture,
+        focal_length: exif.focal_length,
+        raw_width: exif.raw_width,
+        raw_height: exif.raw_height,
+        output_width: exif.output_width,
+        output_height: exif.output_height,
+        colors: exif.colors,
+        color_filter: exif.color_filter,
+        cam_mul: exif.cam_mul,
+        date_taken: cstr_field(exif.date_taken),
+        lens: cstr_field(exif.lens),
+        max_aperture: exif.max_aperture,
+        focal_length_35mm: exif.focal_length_35mm,
+        description: cstr_field(exif.description),
+        artist: cstr_field(exif.artist),
+        ..Default::default()
     }
+}
+
+/// Converts a single RAW file at `input_path` into `output_dir`, returning
+/// a human-readable label plus the result and elapsed time so callers can
+/// print the same per-file timing/reporting regardless of which input
+/// source produced the file.
+fn process_one(input_path: &Path, output_dir: &str) -> (String, Result<(), String>, f64) {
+    let file_name = input_path.file_name().unwrap().to_string_lossy();
+    let input_path_str = input_path.to_string_lossy();
+    let stem = input_path.file_stem().unwrap().to_string_lossy();
+    let output_filename = format!("{}.jpg", stem);
+    let output_path = Path::new(output_dir).join(&output_filename);
+    let output_path_str = output_path.to_string_lossy();
+
+    let start_time = Instant::now();
+    // process_raw_to_jpeg reads get_last_error() internally on failure,
+    // so the error string is always fetched on the same thread that
+    // performed the conversion, matching LibRaw's thread-local state.
+    let result = process_raw_to_jpeg(&input_path_str, &output_path_str, 90);
+    let duration = start_time.elapsed().as_secs_f64();
 
-    let entries = match fs::read_dir(test_raws_dir) {
-        Ok(entries) => entries,
+    (format!("{} -> {}", file_name, output_filename), result, duration)
+}
+
+fn report(label: &str, result: &Result<(), String>, duration: f64) {
+    match result {
+        Ok(()) => println!("  ✅ Success: {} (took {:.2}s)", label, duration),
+        Err(e) => println!("  ❌ Error: {} ({}) (took {:.2}s)", label, e, duration),
+    }
+}
+
+fn run_batch(input_dir: &str, output_dir: &str) {
+    let input_paths = match capture_source::scan_directory(Path::new(input_dir)) {
+        Ok(paths) => paths,
         Err(e) => {
-            eprintln!("Failed to read test_raws directory: {}", e);
+            eprintln!("Failed to read {} directory: {}", input_dir, e);
             return;
         }
     };
 
-    for entry in entries {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(e) => {
-                eprintln!("Error reading directory entry: {}", e);
-                continue;
-            }
-        };
+    println!(
+        "Processing {} file(s) using {} thread(s)",
+        input_paths.len(),
+        get_number_of_threads()
+    );
 
-        let input_path = entry.path();
-        let file_name = input_path.file_name().unwrap().to_string_lossy();
-
-        let lower_name = file_name.to_lowercase();
-        if !(lower_name.ends_with(".raw")
-            || lower_name.ends_with(".cr2")
-            || lower_name.ends_with(".cr3")
-            || lower_name.ends_with(".nef")
-            || lower_name.ends_with(".dng")
-            || lower_name.ends_with(".arw")
-            || lower_name.ends_with(".raf")
-            || lower_name.ends_with(".rw2")
-            || lower_name.ends_with(".orf"))
-        {
-            continue;
-        }
+    let results: Vec<(String, Result<(), String>, f64)> = thread_pool().install(|| {
+        input_paths
+            .par_iter()
+            .map(|input_path| process_one(input_path, output_dir))
+            .collect()
+    });
 
-        let input_path_str = input_path.to_string_lossy();
-        let stem = input_path.file_stem().unwrap().to_string_lossy();
-        let output_filename = format!("{}.jpg", stem);
-        let output_path = Path::new(output_dir).join(&output_filename);
-        let output_path_str = output_path.to_string_lossy();
+    for (label, result, duration) in &results {
+        report(label, result, *duration);
+    }
+}
 
-        println!("Processing: {} -> {}", file_name, output_filename);
+/// Runs as a live tethered-shooting previewer: connects to a camera over
+/// USB via libgphoto2 and converts each newly captured frame as it
+/// arrives, reusing the same per-file timing/reporting as the directory
+/// batch mode.
+fn run_tethered_capture(output_dir: &str) {
+    let mut source = match InputSource::from_config(true, "") {
+        Ok(InputSource::Camera(source)) => source,
+        Ok(InputSource::Directory(_)) => unreachable!("camera mode always returns a Camera"),
+        Err(e) => {
+            eprintln!("Failed to connect to camera: {}", e);
+            return;
+        }
+    };
 
-        let start_time = Instant::now();
+    println!("Connected to camera -- watching for new frames (Ctrl+C to stop)...");
+    let capture_dir = Path::new(output_dir).join("captures");
 
-        match process_raw_to_jpeg(&input_path_str, &output_path_str, 90) {
-            Ok(()) => {
-                let duration = start_time.elapsed();
-                println!(
-                    "  ✅ Success -> {} (took {:.2}s)",
-                    output_filename,
-                    duration.as_secs_f64()
-                );
+    loop {
+        match source.wait_for_new_files(&capture_dir, Duration::from_secs(2)) {
+            Ok(new_files) => {
+                for input_path in new_files {
+                    let (label, result, duration) = process_one(&input_path, output_dir);
+                    report(&label, &result, duration);
+                }
             }
             Err(e) => {
-                let duration = start_time.elapsed();
-                println!("  ❌ Error: {} (took {:.2}s)", e, duration.as_secs_f64());
+                eprintln!("Error polling camera: {}", e);
+                return;
             }
         }
     }
 }
+
+fn main() {
+    println!("RAW to JPEG Converter using LibRaw and libjpeg-turbo");
+    println!("===================================");
+
+    if let Ok(threads) = std::env::var("RAW_PREVIEW_THREADS") {
+        match threads.parse::<usize>() {
+            Ok(n) if n > 0 => set_number_of_threads(n),
+            _ => eprintln!("Ignoring invalid RAW_PREVIEW_THREADS value: {}", threads),
+        }
+    }
+
+    let test_raws_dir = "../test_raws";
+    let output_dir = "../output";
+
+    if let Err(e) = fs::create_dir_all(output_dir) {
+        eprintln!("Failed to create output directory: {}", e);
+        return;
+    }
+
+    // RAW_PREVIEW_SOURCE=camera switches to tethered capture; the
+    // directory scan of `test_raws_dir` remains the default source.
+    let use_camera = std::env::var("RAW_PREVIEW_SOURCE")
+        .map(|v| v.eq_ignore_ascii_case("camera"))
+        .unwrap_or(false);
+
+    if use_camera {
+        run_tethered_capture(output_dir);
+    } else {
+        run_batch(test_raws_dir, output_dir);
+    }
+}
@@ -0,0 +1,361 @@
+/// Metadata stripping/redaction for privacy-safe preview sharing
+///
+/// Extracted RAW previews carry whatever EXIF/XMP the camera embedded --
+/// GPS coordinates, camera serial numbers, the author field in XMP -- which
+/// most users don't want attached to a preview they're about to share.
+/// This walks the JPEG's marker segments and drops (or selectively filters)
+/// the EXIF (`APP1`, `"Exif\0\0"`), XMP (`APP1`, the Adobe XMP URI), and
+/// IPTC (`APP13`, `"Photoshop 3.0\0"`) segments, returning sanitized JPEG
+/// bytes suitable for publishing.
+use std::collections::HashSet;
+use std::convert::TryInto;
+
+const MARKER_PREFIX: u8 = 0xFF;
+const SOS: u8 = 0xDA;
+const APP1: u8 = 0xE1;
+const APP13: u8 = 0xED;
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// EXIF sub-IFD pointer tags. Their value is an offset to another IFD
+/// rather than a leaf value, so they're always walked into rather than
+/// redacted as an opaque tag.
+const EXIF_IFD_POINTER: u16 = 0x8769;
+const GPS_IFD_POINTER: u16 = 0x8825;
+
+/// Upper bound on IFD nesting (IFD0 -> ExifIFD -> GPS/Interop is at most
+/// 3 levels deep in any well-formed file). Paired with `seen` in
+/// `redact_ifd` to guard against a crafted sub-IFD pointer that forms a
+/// cycle back to itself or an ancestor, which would otherwise recurse
+/// until the stack overflows.
+const MAX_IFD_DEPTH: usize = 8;
+
+/// Standalone (payload-less) JPEG markers: SOI, EOI, the restart markers,
+/// and `TEM` have no following length field.
+fn is_standalone_marker(marker: u8) -> bool {
+    marker == 0xD8 || marker == 0xD9 || marker == 0x01 || (0xD0..=0xD7).contains(&marker)
+}
+
+/// Removes all EXIF, XMP, and IPTC metadata from `jpeg`. Equivalent to
+/// `redact_tags(jpeg, &[])`.
+pub fn strip_metadata(jpeg: &[u8]) -> Result<Vec<u8>, String> {
+    redact_tags(jpeg, &[])
+}
+
+/// Rewrites `jpeg`'s EXIF metadata to keep only the tags in `allowed_tags`
+/// (matched against IFD0 and the Exif/GPS sub-IFDs), zeroing the rest in
+/// place. An empty allowlist drops the EXIF segment entirely rather than
+/// leaving a shell of zeroed entries behind. XMP and IPTC segments are
+/// always dropped outright -- selectively filtering an embedded XML/IPTC
+/// packet isn't attempted here.
+pub fn redact_tags(jpeg: &[u8], allowed_tags: &[u16]) -> Result<Vec<u8>, String> {
+    if jpeg.len() < 2 || jpeg[0] != MARKER_PREFIX || jpeg[1] != 0xD8 {
+        return Err("Not a JPEG file (missing SOI marker)".to_string());
+    }
+
+    let mut out = Vec::with_capacity(jpeg.len());
+    out.extend_from_slice(&jpeg[0..2]); // SOI
+    let mut pos = 2usize;
+
+    while pos + 1 < jpeg.len() {
+        if jpeg[pos] != MARKER_PREFIX {
+            return Err(format!("Malformed JPEG: expected marker at offset {}", pos));
+        }
+        let marker = jpeg[pos + 1];
+
+        if is_standalone_marker(marker) {
+            out.extend_from_slice(&jpeg[pos..pos + 2]);
+            pos += 2;
+            continue;
+        }
+
+        if marker == SOS {
+            // Entropy-coded scan data follows with no further segments to
+            // parse; copy the rest of the file (including EOI) verbatim.
+            out.extend_from_slice(&jpeg[pos..]);
+            return Ok(out);
+        }
+
+        if pos + 4 > jpeg.len() {
+            return Err("Malformed JPEG: truncated segment length".to_string());
+        }
+        let seg_len = u16::from_be_bytes(jpeg[pos + 2..pos + 4].try_into().unwrap()) as usize;
+        if seg_len < 2 || pos + 2 + seg_len > jpeg.len() {
+            return Err("Malformed JPEG: invalid segment length".to_string());
+        }
+        let payload = &jpeg[pos + 4..pos + 2 + seg_len];
+
+        if marker == APP13 {
+            // IPTC ("Photoshop 3.0\0") -- dropped unconditionally.
+            pos += 2 + seg_len;
+            continue;
+        }
+
+        if marker == APP1 && payload.starts_with(XMP_HEADER) {
+            // XMP packet -- dropped unconditionally.
+            pos += 2 + seg_len;
+            continue;
+        }
+
+        if marker == APP1 && payload.starts_with(EXIF_HEADER) {
+            if allowed_tags.is_empty() {
+                pos += 2 + seg_len;
+                continue;
+            }
+            let tiff = &payload[EXIF_HEADER.len()..];
+            if let Some(redacted) = redact_exif_tiff(tiff, allowed_tags) {
+                let mut new_payload = Vec::with_capacity(EXIF_HEADER.len() + redacted.len());
+                new_payload.extend_from_slice(EXIF_HEADER);
+                new_payload.extend_from_slice(&redacted);
+                let new_len = (new_payload.len() + 2) as u16;
+                out.push(MARKER_PREFIX);
+                out.push(marker);
+                out.extend_from_slice(&new_len.to_be_bytes());
+                out.extend_from_slice(&new_payload);
+            }
+            // Unparseable TIFF structure -- err on the side of privacy and
+            // drop the whole segment rather than pass it through.
+            pos += 2 + seg_len;
+            continue;
+        }
+
+        // Any other segment (APP0/JFIF, DQT, SOF, DHT, ...) passes through
+        // unchanged.
+        out.extend_from_slice(&jpeg[pos..pos + 2 + seg_len]);
+        pos += 2 + seg_len;
+    }
+
+    Err("Malformed JPEG: reached end of file before SOS/EOI".to_string())
+}
+
+fn read_u16(buf: &[u8], offset: usize, little_endian: bool) -> Option<u16> {
+    let bytes: [u8; 2] = buf.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if little_endian {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+fn read_u32(buf: &[u8], offset: usize, little_endian: bool) -> Option<u32> {
+    let bytes: [u8; 4] = buf.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if little_endian {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn write_u16(buf: &mut [u8], offset: usize, value: u16, little_endian: bool) {
+    let bytes = if little_endian {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    };
+    buf[offset..offset + 2].copy_from_slice(&bytes);
+}
+
+/// TIFF type sizes in bytes, used to tell inline values (stored directly
+/// in the entry's value field) from indirect ones (stored elsewhere in
+/// the blob, referenced by offset).
+fn type_size(type_id: u16) -> usize {
+    match type_id {
+        1 | 2 | 6 | 7 => 1,  // BYTE, ASCII, SBYTE, UNDEFINED
+        3 | 8 => 2,          // SHORT, SSHORT
+        4 | 9 | 11 => 4,     // LONG, SLONG, FLOAT
+        5 | 10 | 12 => 8,    // RATIONAL, SRATIONAL, DOUBLE
+        _ => 4,
+    }
+}
+
+fn redact_exif_tiff(tiff: &[u8], allowed_tags: &[u16]) -> Option<Vec<u8>> {
+    if tiff.len() < 8 {
+        return None;
+    }
+    let little_endian = match &tiff[0..2] {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let mut buf = tiff.to_vec();
+    let ifd0_offset = read_u32(&buf, 4, little_endian)? as usize;
+    let mut seen = HashSet::new();
+    redact_ifd(&mut buf, ifd0_offset, little_endian, allowed_tags, &mut seen, 0)?;
+    Some(buf)
+}
+
+/// Walks one IFD in place, zeroing the tag id (and, for values stored
+/// indirectly, the bytes they point at) of any entry not in
+/// `allowed_tags`. Tag `0` isn't a real EXIF tag, so a zeroed entry is
+/// inert to any reader that looks tags up by id; readers that instead do
+/// a strict sorted binary search over tag ids may be thrown off, which is
+/// an accepted tradeoff for a best-effort redaction pass.
+///
+/// Sub-IFD pointers (`EXIF_IFD_POINTER`/`GPS_IFD_POINTER`) are always
+/// recursed into -- they're containers, not leaf values -- so their
+/// contents are filtered by the same allowlist independently of whether
+/// the pointer tag itself is kept.
+///
+/// `seen` tracks every IFD offset already visited in this walk and
+/// `depth` counts the nesting level; a crafted file whose sub-IFD
+/// pointer repeats an offset (forming a cycle back to itself or an
+/// ancestor) or nests past `MAX_IFD_DEPTH` aborts the walk with `None`
+/// instead of recursing forever.
+fn redact_ifd(
+    buf: &mut Vec<u8>,
+    ifd_offset: usize,
+    little_endian: bool,
+    allowed_tags: &[u16],
+    seen: &mut HashSet<usize>,
+    depth: usize,
+) -> Option<()> {
+    if depth > MAX_IFD_DEPTH || !seen.insert(ifd_offset) {
+        return None;
+    }
+    let count = read_u16(buf, ifd_offset, little_endian)? as usize;
+    let mut sub_ifds = Vec::new();
+
+    for i in 0..count {
+        let entry_offset = ifd_offset + 2 + i * 12;
+        if entry_offset + 12 > buf.len() {
+            return None;
+        }
+        let tag = read_u16(buf, entry_offset, little_endian)?;
+        let type_id = read_u16(buf, entry_offset + 2, little_endian)?;
+        let field_count = read_u32(buf, entry_offset + 4, little_endian)?;
+        let value_field_offset = entry_offset + 8;
+
+        if tag == EXIF_IFD_POINTER || tag == GPS_IFD_POINTER {
+            if let Some(sub_offset) = read_u32(buf, value_field_offset, little_endian) {
+                sub_ifds.push(sub_offset as usize);
+            }
+        }
+
+        if !allowed_tags.contains(&tag) {
+            let value_len = (field_count as usize).saturating_mul(type_size(type_id));
+            if value_len > 4 {
+                if let Some(value_offset) = read_u32(buf, value_field_offset, little_endian) {
+                    let value_offset = value_offset as usize;
+                    if value_offset + value_len <= buf.len() {
+                        for b in &mut buf[value_offset..value_offset + value_len] {
+                            *b = 0;
+                        }
+                    }
+                }
+            }
+            write_u16(buf, entry_offset, 0, little_endian);
+            buf[value_field_offset..value_field_offset + 4].fill(0);
+        }
+    }
+
+    for sub_offset in sub_ifds {
+        redact_ifd(buf, sub_offset, little_endian, allowed_tags, seen, depth + 1);
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut seg = vec![MARKER_PREFIX, marker];
+        seg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+        seg.extend_from_slice(payload);
+        seg
+    }
+
+    /// Builds a minimal JPEG with an EXIF APP1 (IFD0 holding a single
+    /// ASCII `Software` tag, 0x0131), an XMP APP1, and a fake scan/EOI
+    /// tail, for exercising the segment walker without a real encoder.
+    fn make_test_jpeg() -> Vec<u8> {
+        let little_endian = true;
+        // TIFF header (8 bytes) + IFD0 (2 + 1*12 + 4 next-ifd-offset) + value.
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(b"II");
+        tiff.extend_from_slice(&42u16.to_le_bytes());
+        tiff.extend_from_slice(&8u32.to_le_bytes()); // IFD0 at offset 8
+        tiff.extend_from_slice(&1u16.to_le_bytes()); // 1 entry
+        tiff.extend_from_slice(&0x0131u16.to_le_bytes()); // Software tag
+        tiff.extend_from_slice(&2u16.to_le_bytes()); // ASCII
+        tiff.extend_from_slice(&4u32.to_le_bytes()); // count (inline, <=4 bytes)
+        tiff.extend_from_slice(b"ACM\0"); // inline value
+        tiff.extend_from_slice(&0u32.to_le_bytes()); // next IFD offset
+
+        let mut exif_payload = EXIF_HEADER.to_vec();
+        exif_payload.extend_from_slice(&tiff);
+
+        let mut xmp_payload = XMP_HEADER.to_vec();
+        xmp_payload.extend_from_slice(b"<x:xmpmeta/>");
+
+        let mut jpeg = vec![MARKER_PREFIX, 0xD8]; // SOI
+        jpeg.extend(segment(APP1, &exif_payload));
+        jpeg.extend(segment(APP1, &xmp_payload));
+        jpeg.extend([MARKER_PREFIX, SOS, 0x00, 0x02]); // minimal SOS header
+        jpeg.extend_from_slice(&[0xAB, 0xCD]); // fake entropy-coded data
+        jpeg.extend([MARKER_PREFIX, 0xD9]); // EOI
+        jpeg
+    }
+
+    #[test]
+    fn strip_metadata_drops_exif_and_xmp_segments() {
+        let jpeg = make_test_jpeg();
+        let stripped = strip_metadata(&jpeg).unwrap();
+        assert!(!stripped.windows(EXIF_HEADER.len()).any(|w| w == EXIF_HEADER));
+        assert!(!stripped
+            .windows(XMP_HEADER.len())
+            .any(|w| w == XMP_HEADER));
+        // SOI, EOI, and the scan data must still be intact.
+        assert_eq!(&stripped[0..2], &[MARKER_PREFIX, 0xD8]);
+        assert_eq!(&stripped[stripped.len() - 2..], &[MARKER_PREFIX, 0xD9]);
+    }
+
+    #[test]
+    fn redact_tags_keeps_allowlisted_tag_value() {
+        let jpeg = make_test_jpeg();
+        let redacted = redact_tags(&jpeg, &[0x0131]).unwrap();
+        assert!(redacted.windows(EXIF_HEADER.len()).any(|w| w == EXIF_HEADER));
+        assert!(redacted.windows(4).any(|w| w == b"ACM\0"));
+        // XMP is always dropped regardless of the allowlist.
+        assert!(!redacted
+            .windows(XMP_HEADER.len())
+            .any(|w| w == XMP_HEADER));
+    }
+
+    #[test]
+    fn redact_tags_zeroes_non_allowlisted_tag_value() {
+        let jpeg = make_test_jpeg();
+        let redacted = redact_tags(&jpeg, &[0x9999]).unwrap();
+        assert!(!redacted.windows(4).any(|w| w == b"ACM\0"));
+    }
+
+    #[test]
+    fn rejects_non_jpeg_input() {
+        assert!(strip_metadata(b"not a jpeg").is_err());
+    }
+
+    #[test]
+    fn redact_ifd_rejects_self_referencing_sub_ifd_pointer_instead_of_overflowing_stack() {
+        // TIFF header (8 bytes) + IFD0 (2 + 1*12 + 4 next-ifd-offset), whose
+        // one entry is an EXIF_IFD_POINTER pointing back at IFD0 itself.
+        let little_endian = true;
+        let mut tiff = Vec::new();
+        tiff.extend_from_slice(b"II");
+        tiff.extend_from_slice(&42u16.to_le_bytes());
+        tiff.extend_from_slice(&8u32.to_le_bytes()); // IFD0 at offset 8
+        tiff.extend_from_slice(&1u16.to_le_bytes()); // 1 entry
+        tiff.extend_from_slice(&EXIF_IFD_POINTER.to_le_bytes());
+        tiff.extend_from_slice(&4u16.to_le_bytes()); // LONG
+        tiff.extend_from_slice(&1u32.to_le_bytes()); // field count
+        tiff.extend_from_slice(&8u32.to_le_bytes()); // points back at IFD0
+        tiff.extend_from_slice(&0u32.to_le_bytes()); // next-ifd offset
+
+        // Would previously recurse forever; must now return cleanly rather
+        // than overflowing the stack. The cycle is caught (and recursion
+        // stopped) at the point the sub-IFD offset repeats; IFD0 itself
+        // still redacts normally since the cycle is only discovered one
+        // level down.
+        assert!(redact_exif_tiff(&tiff, &[]).is_some());
+    }
+}
@@ -4,10 +4,23 @@
 /// using the LibRaw library through a C++ wrapper, with comprehensive
 /// EXIF data extraction.
 use crate::exif_data::{ExifData, ExifInfo};
+use crate::exif_writer;
+use crate::iptc_data::IptcInfo;
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use std::ptr;
 
+/// Auto-generated LibRaw FFI surface (`libraw_data_t`, `libraw_image_sizes_t`,
+/// `libraw_*` functions, `LibRaw_errors`), produced by `build.rs` when the
+/// `bindgen` feature is enabled. Not used by the hand-written wrapper calls
+/// below; it exists so callers who need direct LibRaw access don't have to
+/// wait on a hand-maintained wrapper update after a LibRaw version bump.
+#[cfg(feature = "bindgen")]
+#[allow(non_camel_case_types, non_snake_case, non_upper_case_globals)]
+pub mod libraw_sys {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
 // Foreign function interface to our C++ wrapper
 unsafe extern "C" {
     fn process_raw_to_jpeg(
@@ -34,10 +47,281 @@ unsafe extern "C" {
         out_size: *mut usize,
         exif_data: *mut ExifData,
     ) -> i32;
+
+    /// Extracts the largest camera-embedded JPEG preview from a RAW file
+    /// (LibRaw's `unpack_thumb`/`dcraw_thumb_writer`) without demosaicing.
+    fn extract_raw_thumb(
+        input_path: *const c_char,
+        output_path: *const c_char,
+        exif_data: *mut ExifData,
+    ) -> i32;
+
+    /// Demosaics and writes a 16-bit-per-channel TIFF.
+    fn process_raw_to_tiff16(
+        input_path: *const c_char,
+        output_path: *const c_char,
+        exif_data: *mut ExifData,
+    ) -> i32;
+    /// Demosaics and writes an 8-bit PNG.
+    fn process_raw_to_png(
+        input_path: *const c_char,
+        output_path: *const c_char,
+        exif_data: *mut ExifData,
+    ) -> i32;
+    /// Demosaics and writes a HEIF image at the given quality (0-100).
+    fn process_raw_to_heif(
+        input_path: *const c_char,
+        output_path: *const c_char,
+        quality: i32,
+        exif_data: *mut ExifData,
+    ) -> i32;
+
+    /// Runs LibRaw's open/unpack far enough to populate `exif_data`
+    /// without demosaicing or encoding an output image at all -- cheaper
+    /// than even `extract_raw_thumb` for callers that only want tags.
+    #[link_name = "extract_raw_metadata"]
+    fn extract_raw_metadata_c(input_path: *const c_char, exif_data: *mut ExifData) -> i32;
+
+    /// Reports whether LibRaw's thumbnail list has a usable entry, without
+    /// unpacking it. Returns non-zero when `extract_raw_thumb` would
+    /// succeed instead of falling back to a full demosaic.
+    fn has_embedded_thumbnail(input_path: *const c_char) -> i32;
+
+    /// Same as `process_raw_to_jpeg`, but demosaic size, output color
+    /// space, output bit depth, and demosaic quality are driven by
+    /// `params` instead of LibRaw's defaults.
+    fn process_raw_to_jpeg_with_options(
+        input_path: *const c_char,
+        output_path: *const c_char,
+        params: *const RawProcessingParams,
+        exif_data: *mut ExifData,
+    ) -> i32;
+
+    /// In-memory counterpart to `extract_raw_thumb`: unpacks the RAW bytes'
+    /// largest embedded preview and hands back a heap buffer (JPEG bytes
+    /// verbatim if the thumbnail is already a JPEG, a freshly re-encoded
+    /// JPEG if it's a bitmap) instead of writing a file. Returns
+    /// `RW_NO_THUMBNAIL` when the RAW carries no usable embedded preview.
+    #[link_name = "extract_raw_thumb_buffer"]
+    fn extract_raw_thumb_buffer_c(
+        data: *const u8,
+        size: usize,
+        out_buf: *mut *mut u8,
+        out_size: *mut usize,
+        exif_data: *mut ExifData,
+    ) -> i32;
+
+    /// Same as `process_raw_bytes_to_jpeg_buffer`, but demosaic size,
+    /// output color space, output bit depth, demosaic quality, white
+    /// balance, and brightness/gamma are driven by `params` instead of
+    /// LibRaw's defaults.
+    #[link_name = "process_raw_bytes_to_jpeg_buffer_with_options"]
+    fn process_raw_bytes_to_jpeg_buffer_with_options_c(
+        data: *const u8,
+        size: usize,
+        params: *const RawProcessingParams,
+        out_buf: *mut *mut u8,
+        out_size: *mut usize,
+        exif_data: *mut ExifData,
+    ) -> i32;
 }
 
 /// Success code returned by the LibRaw wrapper
 const RW_SUCCESS: i32 = 0;
+/// Returned by `extract_raw_thumb` when the RAW has no usable embedded
+/// preview (e.g. it was stripped, or the format doesn't carry one).
+const RW_NO_THUMBNAIL: i32 = 2;
+
+/// Selects which code path `process_raw` takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessingMode {
+    /// Full demosaic via `convert_raw_to_jpeg` -- slow but renders the
+    /// actual sensor data.
+    FullRender,
+    /// Pull the camera-embedded JPEG preview straight out of the RAW.
+    /// Typically 10-50x faster than demosaicing, at the cost of using
+    /// whatever preview size/processing the camera itself baked in.
+    /// Falls back to `FullRender` if the RAW has no usable thumbnail.
+    Preview,
+}
+
+/// The output encoder `convert_raw_to_format` should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// 8-bit JPEG via `process_raw_to_jpeg`.
+    Jpeg,
+    /// 16-bit-per-channel TIFF, useful when the caller wants headroom for
+    /// further editing instead of a final, lossy-compressed preview.
+    Tiff16,
+    /// 8-bit, losslessly-compressed PNG.
+    Png,
+    /// HEIF, which at equal quality settings produces roughly half the
+    /// file size of JPEG.
+    Heif,
+}
+
+/// Output encoding options for `convert_raw_to_format`.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    /// Encoder quality, 0-100. Only `Heif` uses it; `Jpeg`, `Png` and
+    /// `Tiff16` ignore it.
+    pub quality: u8,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            format: OutputFormat::Jpeg,
+            quality: 90,
+        }
+    }
+}
+
+/// Output color space for `convert_raw_to_jpeg_with`, mapped to LibRaw's
+/// numeric `output_color` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// LibRaw `output_color = 1`.
+    Srgb,
+    /// LibRaw `output_color = 2`.
+    AdobeRgb,
+    /// LibRaw `output_color = 3`.
+    Wide,
+    /// LibRaw `output_color = 4`.
+    ProPhoto,
+    /// LibRaw `output_color = 5`.
+    Xyz,
+    /// LibRaw `output_color = 6`.
+    Aces,
+}
+
+impl ColorSpace {
+    fn to_libraw_output_color(self) -> i32 {
+        match self {
+            ColorSpace::Srgb => 1,
+            ColorSpace::AdobeRgb => 2,
+            ColorSpace::Wide => 3,
+            ColorSpace::ProPhoto => 4,
+            ColorSpace::Xyz => 5,
+            ColorSpace::Aces => 6,
+        }
+    }
+}
+
+impl Default for ColorSpace {
+    fn default() -> Self {
+        ColorSpace::Srgb
+    }
+}
+
+/// White balance mode for `RawOptions`, mapped to LibRaw's `use_camera_wb`,
+/// `use_auto_wb`, and `user_mul` settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WhiteBalance {
+    /// Neither `use_camera_wb` nor `use_auto_wb` set, and no `user_mul`
+    /// override -- LibRaw's own built-in default multipliers.
+    Default,
+    /// LibRaw `use_camera_wb = 1`: the as-shot white balance recorded by
+    /// the camera in the RAW file.
+    Camera,
+    /// LibRaw `use_auto_wb = 1`: LibRaw's auto white balance, averaged
+    /// from the image data itself.
+    Auto,
+    /// A manual `user_mul[4]` (R, G1, B, G2) multiplier override.
+    Manual([f32; 4]),
+}
+
+impl Default for WhiteBalance {
+    fn default() -> Self {
+        WhiteBalance::Default
+    }
+}
+
+/// Demosaic/render options for `convert_raw_to_jpeg_with`.
+#[derive(Debug, Clone, Copy)]
+pub struct RawOptions {
+    /// Quarter-resolution fast decode via LibRaw's `half_size` flag.
+    pub half_size: bool,
+    /// Output color space; maps to LibRaw's `output_color` (1-6).
+    pub output_color: ColorSpace,
+    /// Output bit depth per channel: 8 or 16.
+    pub output_bps: u8,
+    /// LibRaw demosaic quality (`user_qual`): 0 (linear) through 3+
+    /// (AHD and better), depending on which demosaic algorithms LibRaw
+    /// was built with.
+    pub user_quality: u8,
+    /// White balance source; see `WhiteBalance`. `half_size` + `Camera` is
+    /// a particularly cheap combination for preview generation, since it
+    /// skips both the full demosaic and LibRaw's own WB estimation pass.
+    pub white_balance: WhiteBalance,
+    /// LibRaw `bright`: output brightness multiplier applied after
+    /// white balance and before gamma. `1.0` is LibRaw's default (no
+    /// adjustment).
+    pub bright: f32,
+    /// LibRaw `gamm[0]`/`gamm[1]`: the output gamma curve's power and toe
+    /// slope. `[2.222, 4.5]` is LibRaw's default (roughly BT.709/sRGB).
+    pub gamma: [f32; 2],
+    /// When true, splice the extracted EXIF (camera make/model, ISO,
+    /// exposure time, F-number, focal length, lens, date-taken, artist,
+    /// description, and an Orientation tag) back into the output JPEG as
+    /// an APP1 segment after conversion. Off by default to preserve the
+    /// prior output-file behavior.
+    pub embed_exif: bool,
+}
+
+impl Default for RawOptions {
+    fn default() -> Self {
+        Self {
+            half_size: false,
+            output_color: ColorSpace::Srgb,
+            output_bps: 8,
+            user_quality: 3,
+            white_balance: WhiteBalance::Default,
+            bright: 1.0,
+            gamma: [2.222, 4.5],
+            embed_exif: false,
+        }
+    }
+}
+
+/// C-compatible processing parameters for `process_raw_to_jpeg_with_options`
+/// and `process_raw_bytes_to_jpeg_buffer_with_options`. Must match the
+/// `RawProcessingParams` struct in `libraw_wrapper.h`.
+#[repr(C)]
+struct RawProcessingParams {
+    half_size: i32,
+    output_color: i32,
+    output_bps: i32,
+    user_quality: i32,
+    use_camera_wb: i32,
+    use_auto_wb: i32,
+    user_mul: [f32; 4],
+    bright: f32,
+    gamma: [f32; 2],
+}
+
+impl From<&RawOptions> for RawProcessingParams {
+    fn from(options: &RawOptions) -> Self {
+        let (use_camera_wb, use_auto_wb, user_mul) = match options.white_balance {
+            WhiteBalance::Default => (0, 0, [0.0; 4]),
+            WhiteBalance::Camera => (1, 0, [0.0; 4]),
+            WhiteBalance::Auto => (0, 1, [0.0; 4]),
+            WhiteBalance::Manual(mul) => (0, 0, mul),
+        };
+        Self {
+            half_size: options.half_size as i32,
+            output_color: options.output_color.to_libraw_output_color(),
+            output_bps: options.output_bps as i32,
+            user_quality: options.user_quality as i32,
+            use_camera_wb,
+            use_auto_wb,
+            user_mul,
+            bright: options.bright,
+            gamma: options.gamma,
+        }
+    }
+}
 
 /// Helper function to safely convert C char arrays to Rust strings
 fn safe_string_from_array(arr: &[c_char]) -> String {
@@ -58,6 +342,93 @@ fn safe_string_from_ptr(ptr: *const c_char) -> String {
     }
 }
 
+/// Builds an all-zero/null `ExifData`, ready to be passed by mutable
+/// reference to an FFI call that will populate it. Centralizes the
+/// struct's fields in one place so a new field only needs to be listed
+/// here instead of at every call site.
+fn zeroed_exif_data() -> ExifData {
+    ExifData {
+        camera_make: [0; 64],
+        camera_model: [0; 64],
+        software: ptr::null(),
+        iso_speed: 0,
+        shutter: 0.0,
+        aperture: 0.0,
+        focal_length: 0.0,
+        raw_width: 0,
+        raw_height: 0,
+        output_width: 0,
+        output_height: 0,
+        colors: 0,
+        color_filter: 0,
+        cam_mul: [0.0; 4],
+        date_taken: ptr::null(),
+        lens: ptr::null(),
+        max_aperture: 0.0,
+        focal_length_35mm: 0,
+        description: ptr::null(),
+        artist: ptr::null(),
+        gps_latitude: 0.0,
+        gps_longitude: 0.0,
+        gps_altitude: 0.0,
+        has_gps: 0,
+        gps_lat_dms: [0.0; 3],
+        gps_lon_dms: [0.0; 3],
+        gps_lat_ref: 0,
+        gps_lon_ref: 0,
+        gps_altitude_ref: 0,
+        location_city: ptr::null(),
+        location_country: ptr::null(),
+        location_sublocation: ptr::null(),
+        embedded_preview_width: 0,
+        embedded_preview_height: 0,
+    }
+}
+
+impl From<&ExifData> for ExifInfo {
+    /// Converts a populated `ExifData` (as filled in by an FFI call) into
+    /// the safe, owned `ExifInfo`. `iptc` is always left at its default --
+    /// IPTC/XMP extraction is a separate call (`iptc_data::extract_iptc`).
+    fn from(exif_data: &ExifData) -> Self {
+        Self {
+            camera_make: safe_string_from_array(&exif_data.camera_make),
+            camera_model: safe_string_from_array(&exif_data.camera_model),
+            software: safe_string_from_ptr(exif_data.software),
+            iso_speed: exif_data.iso_speed,
+            shutter: exif_data.shutter,
+            aperture: exif_data.aperture,
+            focal_length: exif_data.focal_length,
+            raw_width: exif_data.raw_width,
+            raw_height: exif_data.raw_height,
+            output_width: exif_data.output_width,
+            output_height: exif_data.output_height,
+            colors: exif_data.colors,
+            color_filter: exif_data.color_filter,
+            cam_mul: exif_data.cam_mul,
+            date_taken: safe_string_from_ptr(exif_data.date_taken),
+            lens: safe_string_from_ptr(exif_data.lens),
+            max_aperture: exif_data.max_aperture,
+            focal_length_35mm: exif_data.focal_length_35mm,
+            description: safe_string_from_ptr(exif_data.description),
+            artist: safe_string_from_ptr(exif_data.artist),
+            gps_latitude: if exif_data.has_gps != 0 { Some(exif_data.gps_latitude) } else { None },
+            gps_longitude: if exif_data.has_gps != 0 { Some(exif_data.gps_longitude) } else { None },
+            gps_altitude: if exif_data.has_gps != 0 { Some(exif_data.gps_altitude) } else { None },
+            gps_lat_dms: exif_data.gps_lat_dms,
+            gps_lon_dms: exif_data.gps_lon_dms,
+            gps_lat_ref: exif_data.gps_lat_ref as u8 as char,
+            gps_lon_ref: exif_data.gps_lon_ref as u8 as char,
+            gps_altitude_ref: exif_data.gps_altitude_ref,
+            location_city: safe_string_from_ptr(exif_data.location_city),
+            location_country: safe_string_from_ptr(exif_data.location_country),
+            location_sublocation: safe_string_from_ptr(exif_data.location_sublocation),
+            embedded_preview_width: exif_data.embedded_preview_width,
+            embedded_preview_height: exif_data.embedded_preview_height,
+            iptc: IptcInfo::default(),
+        }
+    }
+}
+
 /// Converts a RAW image file to JPEG format and extracts comprehensive EXIF data
 ///
 /// This function uses LibRaw to process RAW files from various camera manufacturers,
@@ -95,69 +466,59 @@ fn safe_string_from_ptr(ptr: *const c_char) -> String {
 /// - Pentax: PEF
 /// - And many more (see file_detector module for complete list)
 pub fn convert_raw_to_jpeg(input_path: &str, output_path: &str) -> Result<ExifInfo, String> {
+    convert_raw_to_jpeg_with(input_path, output_path, &RawOptions::default())
+}
+
+/// Converts a RAW image file to JPEG format with explicit control over
+/// demosaic size, output color space, output bit depth, and demosaic
+/// quality.
+///
+/// `convert_raw_to_jpeg` is a thin wrapper around this function using
+/// `RawOptions::default()`.
+///
+/// # Arguments
+/// * `input_path` - Path to the input RAW file
+/// * `output_path` - Path where the output JPEG will be saved
+/// * `options` - Demosaic/render options
+///
+/// # Returns
+/// * `Ok(ExifInfo)` with extracted EXIF data on success
+/// * `Err(String)` with detailed error message on failure
+pub fn convert_raw_to_jpeg_with(
+    input_path: &str,
+    output_path: &str,
+    options: &RawOptions,
+) -> Result<ExifInfo, String> {
     // Validate and convert input paths to C strings
     let input_cstring = CString::new(input_path)
         .map_err(|e| format!("Invalid input path '{}': {}", input_path, e))?;
     let output_cstring = CString::new(output_path)
         .map_err(|e| format!("Invalid output path '{}': {}", output_path, e))?;
+    let params = RawProcessingParams::from(options);
 
     // Initialize EXIF data structure for LibRaw to populate
-    let mut exif_data = ExifData {
-        camera_make: [0; 64],
-        camera_model: [0; 64],
-        software: ptr::null(),
-        iso_speed: 0,
-        shutter: 0.0,
-        aperture: 0.0,
-        focal_length: 0.0,
-        raw_width: 0,
-        raw_height: 0,
-        output_width: 0,
-        output_height: 0,
-        colors: 0,
-        color_filter: 0,
-        cam_mul: [0.0; 4],
-        date_taken: ptr::null(),
-        lens: ptr::null(),
-        max_aperture: 0.0,
-        focal_length_35mm: 0,
-        description: ptr::null(),
-        artist: ptr::null(),
-    };
+    let mut exif_data = zeroed_exif_data();
 
     // Call the C++ LibRaw wrapper function
     let result = unsafe {
-        process_raw_to_jpeg(
+        process_raw_to_jpeg_with_options(
             input_cstring.as_ptr(),
             output_cstring.as_ptr(),
+            &params,
             &mut exif_data,
         )
     };
 
     if result == RW_SUCCESS {
         // Successfully processed - extract EXIF data from the C structure
-        let exif_info = ExifInfo {
-            camera_make: safe_string_from_array(&exif_data.camera_make),
-            camera_model: safe_string_from_array(&exif_data.camera_model),
-            software: safe_string_from_ptr(exif_data.software),
-            iso_speed: exif_data.iso_speed,
-            shutter: exif_data.shutter,
-            aperture: exif_data.aperture,
-            focal_length: exif_data.focal_length,
-            raw_width: exif_data.raw_width,
-            raw_height: exif_data.raw_height,
-            output_width: exif_data.output_width,
-            output_height: exif_data.output_height,
-            colors: exif_data.colors,
-            color_filter: exif_data.color_filter,
-            cam_mul: exif_data.cam_mul,
-            date_taken: safe_string_from_ptr(exif_data.date_taken),
-            lens: safe_string_from_ptr(exif_data.lens),
-            max_aperture: exif_data.max_aperture,
-            focal_length_35mm: exif_data.focal_length_35mm,
-            description: safe_string_from_ptr(exif_data.description),
-            artist: safe_string_from_ptr(exif_data.artist),
-        };
+        let exif_info = ExifInfo::from(&exif_data);
+
+        if options.embed_exif {
+            // Output from this crate's demosaic path is already oriented
+            // right-side-up, so Orientation is always written as 1 (normal).
+            exif_writer::embed_exif_into_jpeg(output_path, &exif_info, Some(1))?;
+        }
+
         Ok(exif_info)
     } else {
         // Processing failed - retrieve detailed error message from C++ wrapper
@@ -172,33 +533,291 @@ pub fn convert_raw_to_jpeg(input_path: &str, output_path: &str) -> Result<ExifIn
         Err(format!("LibRaw Error {}: {}", result, error_msg))
     }
 }
+/// Extracts the camera-embedded JPEG preview from a RAW file, falling back
+/// to a full demosaic via `convert_raw_to_jpeg` when the RAW carries no
+/// usable embedded thumbnail.
+///
+/// This is the fast path for contact-sheet/triage workflows where a
+/// full-resolution, accurately demosaiced render isn't needed.
+///
+/// # Arguments
+/// * `input_path` - Path to the input RAW file
+/// * `output_path` - Path where the output JPEG will be saved
+///
+/// # Returns
+/// * `Ok(ExifInfo)` with extracted EXIF data on success
+/// * `Err(String)` with detailed error message on failure
+pub fn process_raw_preview(input_path: &str, output_path: &str) -> Result<ExifInfo, String> {
+    let input_cstring = CString::new(input_path)
+        .map_err(|e| format!("Invalid input path '{}': {}", input_path, e))?;
+    let output_cstring = CString::new(output_path)
+        .map_err(|e| format!("Invalid output path '{}': {}", output_path, e))?;
+
+    let mut exif_data = zeroed_exif_data();
+
+    let result = unsafe {
+        extract_raw_thumb(
+            input_cstring.as_ptr(),
+            output_cstring.as_ptr(),
+            &mut exif_data,
+        )
+    };
+
+    if result == RW_SUCCESS {
+        Ok(ExifInfo::from(&exif_data))
+    } else if result == RW_NO_THUMBNAIL {
+        // No embedded preview -- fall back to a full demosaic.
+        convert_raw_to_jpeg(input_path, output_path)
+    } else {
+        let error_msg = unsafe {
+            let error_ptr = get_last_error();
+            if !error_ptr.is_null() {
+                CStr::from_ptr(error_ptr).to_string_lossy().into_owned()
+            } else {
+                "Unknown LibRaw error".to_string()
+            }
+        };
+        Err(format!("LibRaw Error {}: {}", result, error_msg))
+    }
+}
+
+/// Probes whether `input_path` carries a usable camera-embedded preview,
+/// without extracting it. Lets a caller choose between
+/// `process_raw_preview` and a full demosaic up front instead of
+/// discovering the fallback after the fact.
+///
+/// # Arguments
+/// * `input_path` - Path to the input RAW file
+///
+/// # Returns
+/// * `Ok(true)` if the RAW carries an embedded preview LibRaw can unpack
+/// * `Ok(false)` if it doesn't
+/// * `Err(String)` if `input_path` isn't a valid C string
+pub fn has_embedded_preview(input_path: &str) -> Result<bool, String> {
+    let input_cstring = CString::new(input_path)
+        .map_err(|e| format!("Invalid input path '{}': {}", input_path, e))?;
+    let result = unsafe { has_embedded_thumbnail(input_cstring.as_ptr()) };
+    Ok(result != 0)
+}
+
+/// Error from `extract_embedded_preview_bytes`/`extract_embedded_preview_from_path`.
+///
+/// Distinguishes "no embedded preview" from other failures so callers can
+/// fall back to a full demosaic (`convert_raw_to_jpeg`/
+/// `convert_raw_bytes_to_vec`) only when that's actually the reason, rather
+/// than masking unrelated errors (bad input path, corrupt file) behind the
+/// same fallback.
+#[derive(Debug, Clone)]
+pub enum PreviewError {
+    /// The RAW carries no usable embedded preview LibRaw can unpack.
+    NoEmbeddedPreview,
+    /// Extraction failed for some other reason.
+    Failed(String),
+}
+
+impl std::fmt::Display for PreviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreviewError::NoEmbeddedPreview => {
+                write!(f, "RAW file has no usable embedded preview")
+            }
+            PreviewError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// Extracts the camera-embedded JPEG preview from RAW bytes already in
+/// memory, skipping demosaicing entirely -- the bytes-in/bytes-out
+/// counterpart to `process_raw_preview`, for callers (e.g. web
+/// upload handlers) that don't have the RAW file on disk.
+///
+/// Unlike `process_raw_preview`, this does *not* silently fall back to a
+/// full demosaic: it reports
+/// `PreviewError::NoEmbeddedPreview` so the caller can choose to fall back
+/// to `convert_raw_bytes_to_vec` itself (or surface a cheap-preview-only
+/// failure instead).
+///
+/// # Returns
+/// * `Ok((jpeg_bytes, ExifInfo))` on success
+/// * `Err(PreviewError::NoEmbeddedPreview)` if the RAW has no usable
+///   embedded preview
+/// * `Err(PreviewError::Failed(..))` for any other failure
+pub fn extract_embedded_preview_bytes(bytes: &[u8]) -> Result<(Vec<u8>, ExifInfo), PreviewError> {
+    let mut exif_data = zeroed_exif_data();
+
+    let mut out_ptr: *mut u8 = std::ptr::null_mut();
+    let mut out_size: usize = 0;
+
+    let ret = unsafe {
+        extract_raw_thumb_buffer_c(
+            bytes.as_ptr(),
+            bytes.len(),
+            &mut out_ptr as *mut *mut u8,
+            &mut out_size as *mut usize,
+            &mut exif_data,
+        )
+    };
+
+    if ret == RW_NO_THUMBNAIL {
+        return Err(PreviewError::NoEmbeddedPreview);
+    }
+    if ret != RW_SUCCESS {
+        let err = unsafe {
+            let p = get_last_error();
+            if p.is_null() {
+                "LibRaw unknown error".to_string()
+            } else {
+                CStr::from_ptr(p).to_string_lossy().into_owned()
+            }
+        };
+        return Err(PreviewError::Failed(format!("LibRaw error {}: {}", ret, err)));
+    }
+    if out_ptr.is_null() || out_size == 0 {
+        return Err(PreviewError::Failed("No preview data returned".to_string()));
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(out_ptr, out_size) };
+    let jpeg_vec = slice.to_vec();
+    unsafe { free_buffer(out_ptr) };
+
+    let exif_info = ExifInfo::from(&exif_data);
+    Ok((jpeg_vec, exif_info))
+}
+
+/// Path-based sibling of `extract_embedded_preview_bytes`: reads
+/// `input_path` into memory and extracts its embedded preview without
+/// demosaicing.
+///
+/// # Returns
+/// * `Ok((jpeg_bytes, ExifInfo))` on success
+/// * `Err(PreviewError::NoEmbeddedPreview)` if the RAW has no usable
+///   embedded preview
+/// * `Err(PreviewError::Failed(..))` if the file can't be read or
+///   extraction otherwise fails
+pub fn extract_embedded_preview_from_path(
+    input_path: &str,
+) -> Result<(Vec<u8>, ExifInfo), PreviewError> {
+    let bytes = std::fs::read(input_path).map_err(|e| {
+        PreviewError::Failed(format!("Failed to read '{}': {}", input_path, e))
+    })?;
+    extract_embedded_preview_bytes(&bytes)
+}
+
+/// Extracts EXIF metadata from a RAW file without demosaicing or writing
+/// any output image.
+///
+/// This is the cheapest possible path for indexers/catalog tools that
+/// only care about tags: it skips both the full render (`convert_raw_to_jpeg`)
+/// and the embedded-thumbnail extraction (`process_raw_preview`), neither
+/// of which are needed when no preview file is wanted.
+///
+/// # Arguments
+/// * `input_path` - Path to the input RAW file
+///
+/// # Returns
+/// * `Ok(ExifInfo)` with extracted EXIF data on success
+/// * `Err(String)` with detailed error message on failure
+pub fn extract_raw_metadata(input_path: &str) -> Result<ExifInfo, String> {
+    let input_cstring = CString::new(input_path)
+        .map_err(|e| format!("Invalid input path '{}': {}", input_path, e))?;
+
+    let mut exif_data = zeroed_exif_data();
+
+    let result = unsafe { extract_raw_metadata_c(input_cstring.as_ptr(), &mut exif_data) };
+
+    if result == RW_SUCCESS {
+        Ok(ExifInfo::from(&exif_data))
+    } else {
+        let error_msg = unsafe {
+            let error_ptr = get_last_error();
+            if !error_ptr.is_null() {
+                CStr::from_ptr(error_ptr).to_string_lossy().into_owned()
+            } else {
+                "Unknown LibRaw error".to_string()
+            }
+        };
+        Err(format!("LibRaw Error {}: {}", result, error_msg))
+    }
+}
+
+/// Converts a RAW file to JPEG using the given `ProcessingMode`.
+pub fn process_raw(
+    input_path: &str,
+    output_path: &str,
+    mode: ProcessingMode,
+) -> Result<ExifInfo, String> {
+    match mode {
+        ProcessingMode::FullRender => convert_raw_to_jpeg(input_path, output_path),
+        ProcessingMode::Preview => process_raw_preview(input_path, output_path),
+    }
+}
+
+/// Converts a RAW file to the output format selected by `options`,
+/// dispatching to the matching native encoder.
+///
+/// # Arguments
+/// * `input_path` - Path to the input RAW file
+/// * `output_path` - Path where the output image will be saved
+/// * `options` - Output format and quality
+///
+/// # Returns
+/// * `Ok(ExifInfo)` with extracted EXIF data on success
+/// * `Err(String)` with detailed error message on failure
+pub fn convert_raw_to_format(
+    input_path: &str,
+    output_path: &str,
+    options: OutputOptions,
+) -> Result<ExifInfo, String> {
+    let input_cstring = CString::new(input_path)
+        .map_err(|e| format!("Invalid input path '{}': {}", input_path, e))?;
+    let output_cstring = CString::new(output_path)
+        .map_err(|e| format!("Invalid output path '{}': {}", output_path, e))?;
+
+    let mut exif_data = zeroed_exif_data();
+
+    let result = unsafe {
+        match options.format {
+            OutputFormat::Jpeg => {
+                process_raw_to_jpeg(input_cstring.as_ptr(), output_cstring.as_ptr(), &mut exif_data)
+            }
+            OutputFormat::Tiff16 => process_raw_to_tiff16(
+                input_cstring.as_ptr(),
+                output_cstring.as_ptr(),
+                &mut exif_data,
+            ),
+            OutputFormat::Png => {
+                process_raw_to_png(input_cstring.as_ptr(), output_cstring.as_ptr(), &mut exif_data)
+            }
+            OutputFormat::Heif => process_raw_to_heif(
+                input_cstring.as_ptr(),
+                output_cstring.as_ptr(),
+                options.quality as i32,
+                &mut exif_data,
+            ),
+        }
+    };
+
+    if result == RW_SUCCESS {
+        Ok(ExifInfo::from(&exif_data))
+    } else {
+        let error_msg = unsafe {
+            let error_ptr = get_last_error();
+            if !error_ptr.is_null() {
+                CStr::from_ptr(error_ptr).to_string_lossy().into_owned()
+            } else {
+                "Unknown LibRaw error".to_string()
+            }
+        };
+        Err(format!("LibRaw Error {}: {}", result, error_msg))
+    }
+}
+
 /// Accept RAW data as bytes and convert it to JPEG in-memory via the native FFI.
 /// The resulting JPEG preview is written to the provided `output_path`.
 pub fn convert_raw_bytes_to_jpeg(bytes: &[u8], output_path: &str) -> Result<ExifInfo, String> {
     let c_output = CString::new(output_path).map_err(|_| "Invalid output path")?;
 
-    let mut exif_data = ExifData {
-        camera_make: [0; 64],
-        camera_model: [0; 64],
-        software: ptr::null(),
-        iso_speed: 0,
-        shutter: 0.0,
-        aperture: 0.0,
-        focal_length: 0.0,
-        raw_width: 0,
-        raw_height: 0,
-        output_width: 0,
-        output_height: 0,
-        colors: 0,
-        color_filter: 0,
-        cam_mul: [0.0; 4],
-        date_taken: ptr::null(),
-        lens: ptr::null(),
-        max_aperture: 0.0,
-        focal_length_35mm: 0,
-        description: ptr::null(),
-        artist: ptr::null(),
-    };
+    let mut exif_data = zeroed_exif_data();
 
     let ret = unsafe {
         process_raw_bytes_to_jpeg_c(
@@ -209,28 +828,7 @@ pub fn convert_raw_bytes_to_jpeg(bytes: &[u8], output_path: &str) -> Result<Exif
         )
     };
     if ret == RW_SUCCESS {
-        let exif_info = ExifInfo {
-            camera_make: safe_string_from_array(&exif_data.camera_make),
-            camera_model: safe_string_from_array(&exif_data.camera_model),
-            software: safe_string_from_ptr(exif_data.software),
-            iso_speed: exif_data.iso_speed,
-            shutter: exif_data.shutter,
-            aperture: exif_data.aperture,
-            focal_length: exif_data.focal_length,
-            raw_width: exif_data.raw_width,
-            raw_height: exif_data.raw_height,
-            output_width: exif_data.output_width,
-            output_height: exif_data.output_height,
-            colors: exif_data.colors,
-            color_filter: exif_data.color_filter,
-            cam_mul: exif_data.cam_mul,
-            date_taken: safe_string_from_ptr(exif_data.date_taken),
-            lens: safe_string_from_ptr(exif_data.lens),
-            max_aperture: exif_data.max_aperture,
-            focal_length_35mm: exif_data.focal_length_35mm,
-            description: safe_string_from_ptr(exif_data.description),
-            artist: safe_string_from_ptr(exif_data.artist),
-        };
+        let exif_info = ExifInfo::from(&exif_data);
         Ok(exif_info)
     } else {
         let error_msg = unsafe {
@@ -247,28 +845,7 @@ pub fn convert_raw_bytes_to_jpeg(bytes: &[u8], output_path: &str) -> Result<Exif
 
 /// Convert RAW bytes to JPEG in-memory and return JPEG bytes + ExifInfo
 pub fn convert_raw_bytes_to_vec(bytes: &[u8]) -> Result<(Vec<u8>, ExifInfo), String> {
-    let mut exif_data = ExifData {
-        camera_make: [0; 64],
-        camera_model: [0; 64],
-        software: ptr::null(),
-        iso_speed: 0,
-        shutter: 0.0,
-        aperture: 0.0,
-        focal_length: 0.0,
-        raw_width: 0,
-        raw_height: 0,
-        output_width: 0,
-        output_height: 0,
-        colors: 0,
-        color_filter: 0,
-        cam_mul: [0.0; 4],
-        date_taken: ptr::null(),
-        lens: ptr::null(),
-        max_aperture: 0.0,
-        focal_length_35mm: 0,
-        description: ptr::null(),
-        artist: ptr::null(),
-    };
+    let mut exif_data = zeroed_exif_data();
 
     let mut out_ptr: *mut u8 = std::ptr::null_mut();
     let mut out_size: usize = 0;
@@ -306,29 +883,70 @@ pub fn convert_raw_bytes_to_vec(bytes: &[u8]) -> Result<(Vec<u8>, ExifInfo), Str
     unsafe { free_buffer(out_ptr) };
 
     // Convert ExifData
-    let exif_info = ExifInfo {
-        camera_make: safe_string_from_array(&exif_data.camera_make),
-        camera_model: safe_string_from_array(&exif_data.camera_model),
-        software: safe_string_from_ptr(exif_data.software),
-        iso_speed: exif_data.iso_speed,
-        shutter: exif_data.shutter,
-        aperture: exif_data.aperture,
-        focal_length: exif_data.focal_length,
-        raw_width: exif_data.raw_width,
-        raw_height: exif_data.raw_height,
-        output_width: exif_data.output_width,
-        output_height: exif_data.output_height,
-        colors: exif_data.colors,
-        color_filter: exif_data.color_filter,
-        cam_mul: exif_data.cam_mul,
-        date_taken: safe_string_from_ptr(exif_data.date_taken),
-        lens: safe_string_from_ptr(exif_data.lens),
-        max_aperture: exif_data.max_aperture,
-        focal_length_35mm: exif_data.focal_length_35mm,
-        description: safe_string_from_ptr(exif_data.description),
-        artist: safe_string_from_ptr(exif_data.artist),
+    let exif_info = ExifInfo::from(&exif_data);
+
+    Ok((jpeg_vec, exif_info))
+}
+
+/// Convert RAW bytes to JPEG in-memory with explicit control over demosaic
+/// size, output color space, output bit depth, demosaic quality, white
+/// balance, and brightness/gamma.
+///
+/// `convert_raw_bytes_to_vec` is the zero-options default; this is the
+/// bytes-in/bytes-out counterpart to `convert_raw_to_jpeg_with`, for
+/// callers (e.g. web upload handlers) that don't have the RAW file on
+/// disk. The `half_size` + `WhiteBalance::Camera` combination in
+/// particular skips both the full-resolution demosaic and LibRaw's own
+/// white balance estimation pass, which is the cheapest way to render a
+/// preview.
+///
+/// # Returns
+/// * `Ok((jpeg_bytes, ExifInfo))` on success
+/// * `Err(String)` with detailed error message on failure
+pub fn convert_raw_bytes_to_vec_with_options(
+    bytes: &[u8],
+    options: &RawOptions,
+) -> Result<(Vec<u8>, ExifInfo), String> {
+    let params = RawProcessingParams::from(options);
+
+    let mut exif_data = zeroed_exif_data();
+
+    let mut out_ptr: *mut u8 = std::ptr::null_mut();
+    let mut out_size: usize = 0;
+
+    let ret = unsafe {
+        process_raw_bytes_to_jpeg_buffer_with_options_c(
+            bytes.as_ptr(),
+            bytes.len(),
+            &params,
+            &mut out_ptr as *mut *mut u8,
+            &mut out_size as *mut usize,
+            &mut exif_data,
+        )
     };
 
+    if ret != RW_SUCCESS {
+        let err = unsafe {
+            let p = get_last_error();
+            if p.is_null() {
+                "LibRaw unknown error".to_string()
+            } else {
+                CStr::from_ptr(p).to_string_lossy().into_owned()
+            }
+        };
+        return Err(format!("LibRaw error {}: {}", ret, err));
+    }
+
+    if out_ptr.is_null() || out_size == 0 {
+        return Err("No JPEG data returned".to_string());
+    }
+
+    let slice = unsafe { std::slice::from_raw_parts(out_ptr, out_size) };
+    let jpeg_vec = slice.to_vec();
+    unsafe { free_buffer(out_ptr) };
+
+    let exif_info = ExifInfo::from(&exif_data);
+
     Ok((jpeg_vec, exif_info))
 }
 
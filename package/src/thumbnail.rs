@@ -0,0 +1,165 @@
+/// Thumbnail generation with an on-disk cache
+///
+/// `process_raw_preview`/`bmff::extract_preview_and_exif` hand back the
+/// full-size embedded preview, which is still far larger than a gallery
+/// or server thumbnail needs to be. This module decodes that preview (via
+/// the `image` crate), downscales it to a caller-requested size, and
+/// caches the result on disk so repeated requests for the same file
+/// don't re-decode and re-resize every time.
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use image::imageops::FilterType;
+
+/// Resampling filter used when downscaling a thumbnail. Mirrors
+/// `image::imageops::FilterType` rather than re-exporting it directly, so
+/// the `image` crate stays an implementation detail of this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThumbnailFilter {
+    Nearest,
+    Triangle,
+    CatmullRom,
+    Gaussian,
+    Lanczos3,
+}
+
+impl ThumbnailFilter {
+    fn to_image_filter(self) -> FilterType {
+        match self {
+            ThumbnailFilter::Nearest => FilterType::Nearest,
+            ThumbnailFilter::Triangle => FilterType::Triangle,
+            ThumbnailFilter::CatmullRom => FilterType::CatmullRom,
+            ThumbnailFilter::Gaussian => FilterType::Gaussian,
+            ThumbnailFilter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+impl Default for ThumbnailFilter {
+    /// Lanczos3 is the slowest of the five but gives the cleanest
+    /// downscale, which matters more than a few extra milliseconds for a
+    /// result that gets cached and reused.
+    fn default() -> Self {
+        ThumbnailFilter::Lanczos3
+    }
+}
+
+/// Builds the on-disk cache key for a thumbnail request. The source
+/// file's path, its modification time, the requested max dimension, and
+/// the resampling filter all feed the hash, so changing any of them
+/// (including the source file itself changing on disk) produces a
+/// different cache entry instead of serving a stale one.
+fn cache_key(source_path: &str, mtime_secs: u64, max_dimension: u32, filter: ThumbnailFilter) -> String {
+    let mut hasher = DefaultHasher::new();
+    source_path.hash(&mut hasher);
+    mtime_secs.hash(&mut hasher);
+    max_dimension.hash(&mut hasher);
+    filter.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Returns a downscaled JPEG thumbnail for `source_path`, decoding
+/// `preview_path` (the full-size preview already extracted by e.g.
+/// `process_raw_preview` or `bmff::extract_preview_and_exif`) only on a
+/// cache miss.
+///
+/// The cache lives under `cache_dir`, keyed by `source_path` + its
+/// modification time + `max_dimension` + `filter`: editing the source
+/// file invalidates any thumbnail cached for it, and a repeat request for
+/// an unchanged file costs a single `stat` plus a cache-hit file path.
+pub fn get_or_create_thumbnail(
+    source_path: &str,
+    preview_path: &str,
+    cache_dir: &str,
+    max_dimension: u32,
+    filter: ThumbnailFilter,
+) -> Result<String, String> {
+    let mtime_secs = fs::metadata(source_path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Failed to stat '{}': {}", source_path, e))?
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Invalid modification time for '{}': {}", source_path, e))?
+        .as_secs();
+
+    fs::create_dir_all(cache_dir)
+        .map_err(|e| format!("Failed to create cache dir '{}': {}", cache_dir, e))?;
+
+    let key = cache_key(source_path, mtime_secs, max_dimension, filter);
+    let cache_path = Path::new(cache_dir).join(format!("{}.jpg", key));
+
+    if cache_path.exists() {
+        return Ok(cache_path.display().to_string());
+    }
+
+    let preview = image::open(preview_path)
+        .map_err(|e| format!("Failed to decode preview '{}': {}", preview_path, e))?;
+    let thumbnail = preview.resize(max_dimension, max_dimension, filter.to_image_filter());
+    thumbnail
+        .save(&cache_path)
+        .map_err(|e| format!("Failed to write thumbnail to '{}': {}", cache_path.display(), e))?;
+
+    Ok(cache_path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn write_test_preview(path: &Path) {
+        let img = RgbImage::from_fn(200, 100, |x, _y| Rgb([(x % 256) as u8, 0, 0]));
+        img.save(path).expect("failed to write test preview");
+    }
+
+    #[test]
+    fn caches_thumbnail_and_reuses_it_on_hit() {
+        let dir = std::env::temp_dir().join("thumbnail_test_cache_hit");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let source_path = dir.join("source.raw");
+        fs::write(&source_path, b"pretend raw bytes").unwrap();
+        let preview_path = dir.join("preview.jpg");
+        write_test_preview(&preview_path);
+        let cache_dir = dir.join("cache");
+
+        let first = get_or_create_thumbnail(
+            source_path.to_str().unwrap(),
+            preview_path.to_str().unwrap(),
+            cache_dir.to_str().unwrap(),
+            64,
+            ThumbnailFilter::Nearest,
+        )
+        .expect("should produce a thumbnail");
+        assert!(Path::new(&first).exists());
+
+        let second = get_or_create_thumbnail(
+            source_path.to_str().unwrap(),
+            preview_path.to_str().unwrap(),
+            cache_dir.to_str().unwrap(),
+            64,
+            ThumbnailFilter::Nearest,
+        )
+        .expect("should hit the cache");
+        assert_eq!(first, second);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn different_max_dimensions_produce_different_cache_entries() {
+        let key_a = cache_key("photo.cr2", 1_000, 256, ThumbnailFilter::Lanczos3);
+        let key_b = cache_key("photo.cr2", 1_000, 512, ThumbnailFilter::Lanczos3);
+        assert_ne!(key_a, key_b);
+    }
+
+    #[test]
+    fn different_mtimes_produce_different_cache_entries() {
+        let key_a = cache_key("photo.cr2", 1_000, 256, ThumbnailFilter::Lanczos3);
+        let key_b = cache_key("photo.cr2", 2_000, 256, ThumbnailFilter::Lanczos3);
+        assert_ne!(key_a, key_b);
+    }
+}